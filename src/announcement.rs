@@ -0,0 +1,108 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use iced::{button, Button, Column, Element, Length, Row, Text};
+
+use crate::module::Module;
+use crate::theme::{self, Theme};
+use crate::utils::{format_time, html_to_text};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    // LumiNUS doesn't give announcements an ID, so they are identified by a hash of their
+    // module and contents instead.
+    pub id: String,
+    pub module_id: String,
+    pub title: String,
+    // The body of the announcement, converted from HTML to plain text.
+    pub body: String,
+    pub first_seen: Option<SystemTime>,
+    #[serde(default)]
+    pub unread: bool,
+}
+
+// Button states for a displayed announcement.
+#[derive(Debug, Clone, Default)]
+pub struct AnnouncementButtons {
+    read_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum AnnouncementMessage {
+    ToggleRead,
+}
+
+impl Announcement {
+    pub fn new(module_id: String, title: String, description: &str) -> Self {
+        let mut hasher = sha1::Sha1::new();
+        hasher.update(module_id.as_bytes());
+        hasher.update(title.as_bytes());
+        hasher.update(description.as_bytes());
+
+        Announcement {
+            id: hasher.digest().to_string(),
+            module_id,
+            title,
+            body: html_to_text(description),
+            first_seen: Some(SystemTime::now()),
+            unread: true,
+        }
+    }
+
+    pub fn view<'a>(
+        &self,
+        buttons: &'a mut AnnouncementButtons,
+        module: Option<&Module>,
+        theme: Theme,
+    ) -> Element<'a, AnnouncementMessage> {
+        let title = match module {
+            Some(module) => format!("{}: {}", module.code, self.title),
+            None => self.title.clone(),
+        };
+
+        let header = Row::new()
+            .spacing(20)
+            .push(Text::new(title).size(24).width(Length::Fill));
+
+        let header = if self.unread {
+            header.push(Text::new("Unread"))
+        } else {
+            header
+        };
+
+        let header = match self.first_seen {
+            Some(first_seen) => header.push(Text::new(format_time(first_seen))),
+            None => header,
+        };
+
+        let header = header.push(
+            Button::new(
+                &mut buttons.read_button,
+                Text::new(if self.unread {
+                    "Mark as read"
+                } else {
+                    "Mark as unread"
+                }),
+            )
+            .style(theme::Button(theme))
+            .on_press(AnnouncementMessage::ToggleRead),
+        );
+
+        Column::new()
+            .spacing(10)
+            .push(header)
+            .push(Text::new(self.body.clone()))
+            .into()
+    }
+}
+
+// Returns button states for the given number of displayed announcements, reusing the
+// states from the previous time the announcements were displayed.
+pub fn announcement_buttons(
+    buttons: &mut Vec<AnnouncementButtons>,
+    count: usize,
+) -> &mut [AnnouncementButtons] {
+    buttons.resize_with(count, AnnouncementButtons::default);
+    &mut buttons[..]
+}
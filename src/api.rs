@@ -1,6 +1,8 @@
 use futures_util::future;
+use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::ffi::OsString;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
@@ -10,27 +12,70 @@ use fluminurs::resource::{
 };
 use fluminurs::Api;
 
+use crate::announcement::Announcement;
 use crate::data::{DataItems, FetchStatus};
+use crate::local::{read_file_info, set_aside_file, FileInfo};
 use crate::module::Module;
 use crate::resource::{Resource, ResourceState};
 use crate::Error;
 
+#[derive(Debug, Clone)]
+pub enum LoginError {
+    InvalidCredentials,
+    Network,
+    Server,
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                LoginError::InvalidCredentials => "Username or password is incorrect",
+                LoginError::Network =>
+                    "Could not connect to LumiNUS, check your internet connection",
+                LoginError::Server => "LumiNUS could not sign you in, try again later",
+            }
+        )
+    }
+}
+
 pub async fn login(
     username: String,
     password: String,
-) -> Result<(Api, String, String, DataItems<Module>), Error> {
+) -> Result<(Api, String, String, DataItems<Module>), LoginError> {
     let api = Api::with_login(&username, &password)
         .await
-        .map_err(|_| Error {})?
+        .map_err(|e| classify_login_error(&e.to_string()))?
         // TODO: custom ffmpeg location
         .with_ffmpeg("ffmpeg".to_owned());
 
     // TODO: no hardcode!
-    let modules = load_modules(&api, Some("2110".to_string()), SystemTime::now()).await?;
+    let modules = load_modules(&api, Some("2110".to_string()), SystemTime::now())
+        .await
+        .map_err(|_| LoginError::Server)?;
 
     Ok((api, username, password, modules))
 }
 
+// fluminurs describes errors with a fixed message rather than an error type, so the
+// cause is identified by matching the whole message. These are the messages returned
+// by `Api::with_login` in fluminurs' `src/lib.rs` on the `desktop` branch (revision
+// de05a3c), and have to be checked again whenever fluminurs is updated. Requests which
+// fail to be sent at all are network errors, while unexpected responses are treated as
+// server errors.
+const INVALID_CREDENTIALS_ERROR: &str = "Invalid credentials";
+const FAILED_REQUEST_ERROR: &str = "Failed HTTP request";
+
+fn classify_login_error(message: &str) -> LoginError {
+    match message {
+        INVALID_CREDENTIALS_ERROR => LoginError::InvalidCredentials,
+        FAILED_REQUEST_ERROR => LoginError::Network,
+        _ => LoginError::Server,
+    }
+}
+
 // TODO: reduce code duplication with fluminurs
 
 pub async fn load_modules(
@@ -53,13 +98,35 @@ pub async fn load_modules(
     })
 }
 
+// Collects the items loaded from each module, along with the IDs of the modules which
+// were loaded successfully. Only those modules can be used to detect items which have
+// been removed.
+fn loaded_items<T, E>(
+    items: Vec<T>,
+    module_ids: Vec<String>,
+    errors: &[(String, E)],
+    last_updated: SystemTime,
+) -> (DataItems<T>, Vec<String>) {
+    let loaded_module_ids = module_ids
+        .into_iter()
+        .filter(|module_id| !errors.iter().any(|(id, _)| id == module_id))
+        .collect();
+
+    (
+        DataItems {
+            last_updated,
+            items,
+            fetch_status: FetchStatus::Idle,
+        },
+        loaded_module_ids,
+    )
+}
+
 pub async fn load_modules_files(
     api: Api,
     modules: Vec<FluminursModule>,
     last_updated: SystemTime,
-) -> Result<DataItems<ResourceState>, Error> {
-    let include_uploadable_folders = true;
-
+) -> Result<(DataItems<ResourceState>, Vec<String>), Error> {
     let root_dirs = modules
         .iter()
         .filter(|module| module.has_access())
@@ -67,63 +134,138 @@ pub async fn load_modules_files(
             (
                 module.id.clone(),
                 module.workbin_root(|_| PathBuf::new()),
-                module.is_teaching(),
+                module.workbin_root(|_| PathBuf::new()),
             )
         })
         .collect::<Vec<_>>();
+    let module_ids = root_dirs
+        .iter()
+        .map(|root| root.0.clone())
+        .collect::<Vec<_>>();
 
-    let (files, errors) =
-        future::join_all(root_dirs.into_iter().map(|(module_id, root_dir, _)| async {
-            let files = root_dir
-                .load(&api, include_uploadable_folders)
-                .await
-                .map(|mut files| {
-                    // to avoid duplicate files from being corrupted,
-                    // we append the id to duplicate resources
-                    sort_and_make_all_paths_unique(&mut files);
-                    files
-                });
+    // fluminurs doesn't say which folders allow uploads, so the workbin is also loaded
+    // without them. Files which only appear when uploadable folders are included are in
+    // one of them.
+    let (files, errors) = future::join_all(root_dirs.into_iter().map(
+        |(module_id, root_dir, root_dir_without_uploadable)| async {
+            let (files, files_without_uploadable) = future::join(
+                root_dir.load(&api, true),
+                root_dir_without_uploadable.load(&api, false),
+            )
+            .await;
+            let files = files.and_then(|files| {
+                files_without_uploadable
+                    .map(|files_without_uploadable| (files, files_without_uploadable))
+            });
+            let files = files.map(|(mut files, mut files_without_uploadable)| {
+                // to avoid duplicate files from being corrupted,
+                // we append the id to duplicate resources
+                sort_and_make_all_paths_unique(&mut files);
+                sort_and_make_all_paths_unique(&mut files_without_uploadable);
+                let not_uploadable = files_without_uploadable
+                    .iter()
+                    .map(|file| file.path().to_path_buf())
+                    .collect::<HashSet<_>>();
+                (files, not_uploadable)
+            });
 
             (module_id, files)
-        }))
-        .await
-        .into_iter()
-        .fold(
-            (vec![], vec![]),
-            move |(mut ok, mut err), (module_id, res)| {
-                match res {
-                    Ok(dir) => {
-                        let mut resources = dir
-                            .into_iter()
-                            .map(|video| {
-                                ResourceState::new(Resource::File(video), module_id.clone())
-                            })
-                            .collect::<Vec<_>>();
-                        ok.append(&mut resources);
-                    }
-                    Err(e) => {
-                        err.push((module_id, e));
-                    }
+        },
+    ))
+    .await
+    .into_iter()
+    .fold(
+        (vec![], vec![]),
+        move |(mut ok, mut err), (module_id, res)| {
+            match res {
+                Ok((dir, not_uploadable)) => {
+                    let mut resources = dir
+                        .into_iter()
+                        .map(|file| {
+                            let in_uploadable_folder = !not_uploadable.contains(file.path());
+                            let mut resource =
+                                ResourceState::new(Resource::File(file), module_id.clone());
+                            resource.metadata.in_uploadable_folder = in_uploadable_folder;
+                            resource
+                        })
+                        .collect::<Vec<_>>();
+                    ok.append(&mut resources);
+                }
+                Err(e) => {
+                    err.push((module_id, e));
                 }
-                (ok, err)
-            },
-        );
-    for (module_id, e) in errors {
+            }
+            (ok, err)
+        },
+    );
+    for (module_id, e) in errors.iter() {
         println!("Failed loading module files: {} {}", module_id, e);
     }
 
-    Ok(DataItems {
+    Ok(loaded_items(files, module_ids, &errors, last_updated))
+}
+
+pub async fn load_modules_announcements(
+    api: Api,
+    modules: Vec<FluminursModule>,
+    last_updated: SystemTime,
+) -> Result<(DataItems<Announcement>, Vec<String>), Error> {
+    let modules = modules
+        .into_iter()
+        .filter(|module| module.has_access())
+        .collect::<Vec<_>>();
+
+    let (announcements, errors) = future::join_all(modules.iter().map(|module| async {
+        (
+            module.id.clone(),
+            module.get_announcements(&api, false).await,
+        )
+    }))
+    .await
+    .into_iter()
+    .fold(
+        (vec![], vec![]),
+        move |(mut ok, mut err), (module_id, res)| {
+            match res {
+                Ok(announcements) => {
+                    let mut announcements = announcements
+                        .into_iter()
+                        .map(|announcement| {
+                            Announcement::new(
+                                module_id.clone(),
+                                announcement.title,
+                                &announcement.description,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    ok.append(&mut announcements);
+                }
+                Err(e) => {
+                    err.push((module_id, e));
+                }
+            }
+            (ok, err)
+        },
+    );
+    for (module_id, e) in errors.iter() {
+        println!("Failed loading module announcements: {} {}", module_id, e);
+    }
+
+    let module_ids = modules.into_iter().map(|module| module.id).collect();
+
+    Ok(loaded_items(
+        announcements,
+        module_ids,
+        &errors,
         last_updated,
-        items: files,
-        fetch_status: FetchStatus::Idle,
-    })
+    ))
 }
 
 pub async fn load_modules_multimedia(
     api: Api,
     modules: Vec<FluminursModule>,
     last_updated: SystemTime,
-) -> Result<DataItems<ResourceState>, Error> {
+) -> Result<(DataItems<ResourceState>, Vec<String>), Error> {
     let multimedias = modules
         .iter()
         .filter(|module| module.has_access())
@@ -134,6 +276,10 @@ pub async fn load_modules_multimedia(
             )
         })
         .collect::<Vec<_>>();
+    let module_ids = multimedias
+        .iter()
+        .map(|root| root.0.clone())
+        .collect::<Vec<_>>();
 
     let (videos, errors) = future::join_all(multimedias.into_iter().map(
         |(module_id, multimedia)| async {
@@ -177,22 +323,18 @@ pub async fn load_modules_multimedia(
         },
     );
 
-    for (module_id, e) in errors {
+    for (module_id, e) in errors.iter() {
         println!("Failed loading module multimedia: {} {}", module_id, e);
     }
 
-    Ok(DataItems {
-        last_updated,
-        items: videos,
-        fetch_status: FetchStatus::Idle,
-    })
+    Ok(loaded_items(videos, module_ids, &errors, last_updated))
 }
 
 pub async fn load_modules_weblectures(
     api: Api,
     modules: Vec<FluminursModule>,
     last_updated: SystemTime,
-) -> Result<DataItems<ResourceState>, Error> {
+) -> Result<(DataItems<ResourceState>, Vec<String>), Error> {
     let weblectures = modules
         .iter()
         .filter(|module| module.has_access())
@@ -203,6 +345,10 @@ pub async fn load_modules_weblectures(
             )
         })
         .collect::<Vec<_>>();
+    let module_ids = weblectures
+        .iter()
+        .map(|root| root.0.clone())
+        .collect::<Vec<_>>();
 
     let (files, errors) = future::join_all(weblectures.into_iter().map(
         |(module_id, weblecture)| async {
@@ -241,22 +387,18 @@ pub async fn load_modules_weblectures(
         },
     );
 
-    for (module_id, e) in errors {
+    for (module_id, e) in errors.iter() {
         println!("Failed loading module web lecture: {} {}", module_id, e);
     }
 
-    Ok(DataItems {
-        last_updated,
-        items: files,
-        fetch_status: FetchStatus::Idle,
-    })
+    Ok(loaded_items(files, module_ids, &errors, last_updated))
 }
 
 pub async fn load_modules_conferences(
     api: Api,
     modules: Vec<FluminursModule>,
     last_updated: SystemTime,
-) -> Result<DataItems<ResourceState>, Error> {
+) -> Result<(DataItems<ResourceState>, Vec<String>), Error> {
     let conferences = modules
         .iter()
         .filter(|module| module.has_access())
@@ -267,6 +409,10 @@ pub async fn load_modules_conferences(
             )
         })
         .collect::<Vec<_>>();
+    let module_ids = conferences
+        .iter()
+        .map(|root| root.0.clone())
+        .collect::<Vec<_>>();
 
     let (zoom_recordings, errors) = future::join_all(conferences.into_iter().map(
         |(module_id, conference)| async {
@@ -305,15 +451,16 @@ pub async fn load_modules_conferences(
         },
     );
 
-    for (module_id, e) in errors {
+    for (module_id, e) in errors.iter() {
         println!("Failed loading module conferences: {} {}", module_id, e);
     }
 
-    Ok(DataItems {
+    Ok(loaded_items(
+        zoom_recordings,
+        module_ids,
+        &errors,
         last_updated,
-        items: zoom_recordings,
-        fetch_status: FetchStatus::Idle,
-    })
+    ))
 }
 
 fn make_temp_file_name(name: &OsStr) -> OsString {
@@ -324,29 +471,92 @@ fn make_temp_file_name(name: &OsStr) -> OsString {
     res
 }
 
+#[derive(Debug, Clone)]
+pub struct DownloadedResource {
+    pub path: PathBuf,
+    pub info: Option<FileInfo>,
+    pub local_copy_path: Option<PathBuf>,
+    pub previous_version_path: Option<PathBuf>,
+}
+
 pub async fn download_resource(
     api: Api,
     resource: Resource,
     download_dir: Option<PathBuf>,
     download_path: PathBuf,
-    path: PathBuf,
+    replace: bool,
+    recorded_hash: Option<String>,
+    recorded_version: Option<SystemTime>,
+    keep_previous_version: bool,
     // overwrite_mode: OverwriteMode,
-) -> Result<PathBuf, Error> {
-    match resource {
+) -> Result<DownloadedResource, Error> {
+    let filepath = download_dir
+        .clone()
+        .unwrap_or_else(|| Path::new(".").to_path_buf())
+        .join(&download_path);
+
+    // Move an outdated local file aside, so that the newer version can be downloaded in
+    // its place. Locally modified files are always kept as a separate copy.
+    let set_aside = if replace {
+        set_aside_file(&filepath, recorded_hash, recorded_version)
+            .await
+            .map_err(|_| Error {})?
+    } else {
+        None
+    };
+    let existed = set_aside.is_none() && tokio::fs::metadata(&filepath).await.is_ok();
+
+    let result = match resource {
         Resource::File(resource) => {
-            download_fluminurs_resource(api, resource, download_dir, download_path, path).await
+            download_fluminurs_resource(api, resource, download_dir, download_path).await
         }
         Resource::InternalVideo(resource) => {
-            download_fluminurs_resource(api, resource, download_dir, download_path, path).await
+            download_fluminurs_resource(api, resource, download_dir, download_path).await
         }
         Resource::ExternalVideo(resource) => {
-            download_fluminurs_resource(api, resource, download_dir, download_path, path).await
+            download_fluminurs_resource(api, resource, download_dir, download_path).await
         }
         Resource::WebLectureVideo(resource) => {
-            download_fluminurs_resource(api, resource, download_dir, download_path, path).await
+            download_fluminurs_resource(api, resource, download_dir, download_path).await
         }
         Resource::ZoomRecording(resource) => {
-            download_zoom_recording(api, resource, download_dir, download_path, path).await
+            download_zoom_recording(api, resource, download_dir, download_path).await
+        }
+    };
+
+    match result {
+        Ok(path) => {
+            // Files which were already present might have been modified locally, so we
+            // only record the hash of newly downloaded files.
+            let info = if existed {
+                None
+            } else {
+                read_file_info(&path).await
+            };
+            let (local_copy_path, previous_version_path) = match set_aside {
+                Some(set_aside) if set_aside.modified => (Some(set_aside.path), None),
+                Some(set_aside) if keep_previous_version => (None, Some(set_aside.path)),
+                Some(set_aside) => {
+                    let _ = tokio::fs::remove_file(set_aside.path).await;
+                    (None, None)
+                }
+                None => (None, None),
+            };
+
+            Ok(DownloadedResource {
+                path,
+                info,
+                local_copy_path,
+                previous_version_path,
+            })
+        }
+        Err(e) => {
+            // Restore the previous version if the newer version couldn't be downloaded.
+            if let Some(set_aside) = set_aside {
+                let _ = tokio::fs::rename(set_aside.path, &filepath).await;
+            }
+
+            Err(e)
         }
     }
 }
@@ -356,7 +566,6 @@ pub async fn download_fluminurs_resource<T: FluminursResource>(
     file: T,
     download_dir: Option<PathBuf>,
     path: PathBuf,
-    return_path: PathBuf,
     // overwrite_mode: OverwriteMode,
 ) -> Result<PathBuf, Error> {
     // Use the current working directory if we can't get a default download location.
@@ -369,27 +578,29 @@ pub async fn download_fluminurs_resource<T: FluminursResource>(
     let temp_path = dest_path
         .join(path.parent().unwrap())
         .join(make_temp_file_name(path.file_name().unwrap()));
+    // The full path to the downloaded file is returned, so that it can be opened directly.
     let filepath = dest_path.join(path.clone());
 
-    match file
+    let result = file
         .download(&api, &filepath, &temp_path, OverwriteMode::Skip)
-        .await
-    {
+        .await;
+
+    match result {
         Ok(OverwriteResult::NewFile) => {
             println!("Downloaded to {}", path.to_string_lossy());
-            Ok(return_path)
+            Ok(filepath)
         }
         Ok(OverwriteResult::AlreadyHave) => {
             println!("File already exists: {}", path.to_string_lossy());
-            Ok(return_path)
+            Ok(filepath)
         }
         Ok(OverwriteResult::Skipped) => {
             println!("Skipped {}", path.to_string_lossy());
-            Ok(return_path)
+            Ok(filepath)
         }
         Ok(OverwriteResult::Overwritten) => {
             println!("Updated {}", path.to_string_lossy());
-            Ok(return_path)
+            Ok(filepath)
         }
         Ok(OverwriteResult::Renamed { renamed_path }) => {
             // TODO: handle renamed files
@@ -398,7 +609,7 @@ pub async fn download_fluminurs_resource<T: FluminursResource>(
                 path.to_string_lossy(),
                 renamed_path.to_string_lossy()
             );
-            Ok(return_path)
+            Ok(filepath)
         }
         Err(e) => {
             println!("Failed to download file: {}", e);
@@ -412,18 +623,43 @@ pub async fn download_zoom_recording<T: FluminursResource>(
     file: T,
     download_dir: Option<PathBuf>,
     download_path: PathBuf,
-    path: PathBuf,
     // overwrite_mode: OverwriteMode,
 ) -> Result<PathBuf, Error> {
     match api.login_zoom().await {
         Err(e) => {
             println!("Failed to log in to Zoom: {}", e);
             // TODO
-            download_fluminurs_resource(api, file, download_dir, download_path, path).await
+            download_fluminurs_resource(api, file, download_dir, download_path).await
         }
         Ok(_) => {
             println!("Logged in to Zoom");
-            download_fluminurs_resource(api, file, download_dir, download_path, path).await
+            download_fluminurs_resource(api, file, download_dir, download_path).await
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_login_error_matches_fluminurs_messages() {
+        assert!(matches!(
+            classify_login_error("Invalid credentials"),
+            LoginError::InvalidCredentials
+        ));
+        assert!(matches!(
+            classify_login_error("Failed HTTP request"),
+            LoginError::Network
+        ));
+        assert!(matches!(
+            classify_login_error("Unable to deserialize JSON"),
+            LoginError::Server
+        ));
+        // Only whole messages are matched.
+        assert!(matches!(
+            classify_login_error("Invalid credentials for HTTP request"),
+            LoginError::Server
+        ));
+    }
+}
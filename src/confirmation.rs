@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use iced::{button, Align, Button, Column, Command, Element, Row, Rule, Text};
+
+use crate::message::Message;
+use crate::resource::ResourceType;
+use crate::theme::{self, Theme};
+
+// Local copies waiting to be deleted, which have to be confirmed first since deleting
+// them can't be undone.
+#[derive(Debug, Clone)]
+pub struct DeleteConfirmation {
+    pending: Option<(ResourceType, Vec<(String, PathBuf)>)>,
+    confirm_button: button::State,
+    cancel_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum DeleteConfirmationMessage {
+    Confirm,
+    Cancel,
+}
+
+impl DeleteConfirmation {
+    pub fn default() -> Self {
+        DeleteConfirmation {
+            pending: None,
+            confirm_button: button::State::new(),
+            cancel_button: button::State::new(),
+        }
+    }
+
+    // Asks to delete the local copies of the resources with the given module IDs and paths.
+    pub fn request(&mut self, resource_type: ResourceType, resources: Vec<(String, PathBuf)>) {
+        self.pending = if resources.is_empty() {
+            None
+        } else {
+            Some((resource_type, resources))
+        };
+    }
+
+    pub fn update(&mut self, message: DeleteConfirmationMessage) -> Command<Message> {
+        match message {
+            DeleteConfirmationMessage::Confirm => match self.pending.take() {
+                Some(pending) => {
+                    Command::perform(async move { pending }, Message::DeleteLocalResources)
+                }
+                None => Command::none(),
+            },
+            DeleteConfirmationMessage::Cancel => {
+                self.pending = None;
+                Command::none()
+            }
+        }
+    }
+
+    pub fn view(&mut self, theme: Theme) -> Option<Element<DeleteConfirmationMessage>> {
+        let count = match &self.pending {
+            Some((_, resources)) => resources.len(),
+            None => return None,
+        };
+
+        let content = Row::new()
+            .spacing(20)
+            .align_items(Align::Center)
+            .push(Text::new(format!(
+                "Delete the local {}? Files which have been changed locally will be kept.",
+                if count == 1 {
+                    "copy of 1 file".to_string()
+                } else {
+                    format!("copies of {} files", count)
+                }
+            )))
+            .push(
+                Button::new(&mut self.confirm_button, Text::new("Delete"))
+                    .style(theme::Button(theme))
+                    .on_press(DeleteConfirmationMessage::Confirm),
+            )
+            .push(
+                Button::new(&mut self.cancel_button, Text::new("Cancel"))
+                    .style(theme::Button(theme))
+                    .on_press(DeleteConfirmationMessage::Cancel),
+            );
+
+        Some(
+            Column::new()
+                .spacing(5)
+                .push(content)
+                .push(Rule::horizontal(0).style(theme::Divider(theme)))
+                .into(),
+        )
+    }
+}
@@ -1,10 +1,12 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
+use crate::announcement::Announcement;
 use crate::module::Module;
-use crate::resource::ResourceState;
+use crate::resource::{ResourceState, ResourceType, WatchStatus};
 use crate::storage::{get_project_dirs, Storage};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +16,8 @@ pub struct Data {
     pub multimedia: DataItems<ResourceState>,
     pub weblectures: DataItems<ResourceState>,
     pub conferences: DataItems<ResourceState>,
+    #[serde(default)]
+    pub announcements: DataItems<Announcement>,
 
     #[serde(skip)]
     dirty: bool,
@@ -53,6 +57,42 @@ impl Default for FetchStatus {
     }
 }
 
+impl DataItems<Module> {
+    pub fn unseen_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|module| module.is_taking && module.unseen)
+            .count()
+    }
+}
+
+impl DataItems<Announcement> {
+    pub fn unread_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|announcement| announcement.unread)
+            .count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for announcement in self.items.iter_mut() {
+            announcement.unread = false;
+        }
+    }
+}
+
+impl DataItems<ResourceState> {
+    pub fn unseen_count(&self) -> usize {
+        self.items.iter().filter(|resource| resource.unseen).count()
+    }
+
+    pub fn mark_all_seen(&mut self) {
+        for resource in self.items.iter_mut() {
+            resource.unseen = false;
+        }
+    }
+}
+
 impl Data {
     pub fn default() -> Self {
         Data {
@@ -61,6 +101,7 @@ impl Data {
             multimedia: DataItems::default(),
             weblectures: DataItems::default(),
             conferences: DataItems::default(),
+            announcements: DataItems::default(),
             dirty: false,
             saving: false,
         }
@@ -69,6 +110,60 @@ impl Data {
     pub fn mark_dirty(&mut self) {
         self.dirty = true;
     }
+
+    // Counts the number of unseen resources of every type for each module.
+    pub fn unseen_counts_by_module(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+
+        for resource in self
+            .files
+            .items
+            .iter()
+            .chain(self.multimedia.items.iter())
+            .chain(self.weblectures.items.iter())
+            .chain(self.conferences.items.iter())
+            .filter(|resource| resource.unseen)
+        {
+            *counts.entry(resource.module_id.clone()).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
+    // Counts the number of watched videos and the total number of videos of each type
+    // for each module.
+    pub fn watched_counts_by_module(&self) -> HashMap<String, Vec<(ResourceType, usize, usize)>> {
+        let mut counts: HashMap<String, Vec<(ResourceType, usize, usize)>> = HashMap::new();
+
+        for (resource_type, items) in [
+            (ResourceType::Multimedia, &self.multimedia),
+            (ResourceType::Weblecture, &self.weblectures),
+            (ResourceType::Conference, &self.conferences),
+        ]
+        .iter()
+        {
+            let mut type_counts: HashMap<&str, (usize, usize)> = HashMap::new();
+            for resource in items.items.iter().filter(|resource| resource.is_video()) {
+                let (watched, total) = type_counts
+                    .entry(resource.module_id.as_str())
+                    .or_insert((0, 0));
+                if resource.watch_status == WatchStatus::Watched {
+                    *watched += 1;
+                }
+                *total += 1;
+            }
+
+            for (module_id, (watched, total)) in type_counts {
+                counts.entry(module_id.to_string()).or_default().push((
+                    *resource_type,
+                    watched,
+                    total,
+                ));
+            }
+        }
+
+        counts
+    }
 }
 
 impl Storage for Data {
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::SystemTime;
+
+use iced::{pick_list, text_input, Align, Element, Length, PickList, Row, Text, TextInput};
+
+use chrono::{Local, NaiveDate, TimeZone};
+
+use crate::module::Module;
+use crate::resource::{ResourceState, WatchStatus};
+use crate::theme::{self, Theme};
+
+#[derive(Debug, Clone)]
+pub struct ResourceFilter {
+    query: String,
+    download_state: DownloadState,
+    watch_state: WatchState,
+    from: String,
+    to: String,
+    query_input: text_input::State,
+    download_state_list: pick_list::State<DownloadState>,
+    watch_state_list: pick_list::State<WatchState>,
+    from_input: text_input::State,
+    to_input: text_input::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum ResourceFilterMessage {
+    QueryEdited(String),
+    DownloadStateSelected(DownloadState),
+    WatchStateSelected(WatchState),
+    FromEdited(String),
+    ToEdited(String),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DownloadState {
+    All,
+    Downloaded,
+    NotDownloaded,
+}
+
+impl DownloadState {
+    const ALL: [DownloadState; 3] = [
+        DownloadState::All,
+        DownloadState::Downloaded,
+        DownloadState::NotDownloaded,
+    ];
+}
+
+impl fmt::Display for DownloadState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DownloadState::All => "All",
+                DownloadState::Downloaded => "Downloaded",
+                DownloadState::NotDownloaded => "Not downloaded",
+            }
+        )
+    }
+}
+
+// Filtering by watch status only includes videos.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WatchState {
+    All,
+    Status(WatchStatus),
+}
+
+impl WatchState {
+    const ALL: [WatchState; 4] = [
+        WatchState::All,
+        WatchState::Status(WatchStatus::Unwatched),
+        WatchState::Status(WatchStatus::PartiallyWatched),
+        WatchState::Status(WatchStatus::Watched),
+    ];
+}
+
+impl fmt::Display for WatchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchState::All => write!(f, "Any watch status"),
+            WatchState::Status(watch_status) => write!(f, "{}", watch_status),
+        }
+    }
+}
+
+impl ResourceFilter {
+    pub fn default() -> Self {
+        ResourceFilter {
+            query: "".to_string(),
+            download_state: DownloadState::All,
+            watch_state: WatchState::All,
+            from: "".to_string(),
+            to: "".to_string(),
+            query_input: text_input::State::new(),
+            download_state_list: pick_list::State::default(),
+            watch_state_list: pick_list::State::default(),
+            from_input: text_input::State::new(),
+            to_input: text_input::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: ResourceFilterMessage) {
+        match message {
+            ResourceFilterMessage::QueryEdited(query) => self.query = query,
+            ResourceFilterMessage::DownloadStateSelected(download_state) => {
+                self.download_state = download_state
+            }
+            ResourceFilterMessage::WatchStateSelected(watch_state) => {
+                self.watch_state = watch_state
+            }
+            ResourceFilterMessage::FromEdited(from) => self.from = from,
+            ResourceFilterMessage::ToEdited(to) => self.to = to,
+        }
+    }
+
+    // Whether any filter has been set.
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+            || self.download_state != DownloadState::All
+            || self.watch_state != WatchState::All
+            || parse_date(&self.from).is_some()
+            || parse_date(&self.to).is_some()
+    }
+
+    pub fn matches(&self, resource: &ResourceState, modules_map: &HashMap<String, Module>) -> bool {
+        let module = modules_map.get(&resource.module_id);
+        let path = resource.path.to_string_lossy().to_lowercase();
+
+        // Every search term has to match either the path of the resource (which includes
+        // its file name), or the module code or name.
+        let matches_query = self.query.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+
+            path.contains(&term)
+                || module.map_or(false, |module| {
+                    module.code.to_lowercase().contains(&term)
+                        || module.name.to_lowercase().contains(&term)
+                })
+        });
+
+        let matches_download_state = match self.download_state {
+            DownloadState::All => true,
+            DownloadState::Downloaded => resource.download_path.is_some(),
+            DownloadState::NotDownloaded => resource.download_path.is_none(),
+        };
+
+        let matches_watch_state = match self.watch_state {
+            WatchState::All => true,
+            WatchState::Status(watch_status) => {
+                resource.is_video() && resource.watch_status == watch_status
+            }
+        };
+
+        // The date range is inclusive of both the start and end dates.
+        let matches_from =
+            parse_date(&self.from).map_or(true, |from| resource.last_updated >= start_of_day(from));
+        let matches_to =
+            parse_date(&self.to).map_or(true, |to| resource.last_updated < start_of_day(to.succ()));
+
+        matches_query && matches_download_state && matches_watch_state && matches_from && matches_to
+    }
+
+    pub fn view(&mut self, theme: Theme) -> Element<ResourceFilterMessage> {
+        let query_input = TextInput::new(
+            &mut self.query_input,
+            "Search by name, path or module",
+            &self.query,
+            ResourceFilterMessage::QueryEdited,
+        )
+        .padding(5)
+        .style(theme::TextInput(theme));
+
+        let download_state_list = PickList::new(
+            &mut self.download_state_list,
+            &DownloadState::ALL[..],
+            Some(self.download_state),
+            ResourceFilterMessage::DownloadStateSelected,
+        )
+        .style(theme::PickList(theme));
+
+        let watch_state_list = PickList::new(
+            &mut self.watch_state_list,
+            &WatchState::ALL[..],
+            Some(self.watch_state),
+            ResourceFilterMessage::WatchStateSelected,
+        )
+        .style(theme::PickList(theme));
+
+        let from_input = TextInput::new(
+            &mut self.from_input,
+            "From (dd/mm/yyyy)",
+            &self.from,
+            ResourceFilterMessage::FromEdited,
+        )
+        .width(Length::Units(150))
+        .padding(5)
+        .style(theme::TextInput(theme));
+
+        let to_input = TextInput::new(
+            &mut self.to_input,
+            "To (dd/mm/yyyy)",
+            &self.to,
+            ResourceFilterMessage::ToEdited,
+        )
+        .width(Length::Units(150))
+        .padding(5)
+        .style(theme::TextInput(theme));
+
+        Row::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(query_input)
+            .push(download_state_list)
+            .push(watch_state_list)
+            .push(Text::new("Updated"))
+            .push(from_input)
+            .push(to_input)
+            .into()
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%d/%m/%Y").ok()
+}
+
+fn start_of_day(date: NaiveDate) -> SystemTime {
+    Local
+        .from_local_datetime(&date.and_hms(0, 0, 0))
+        .earliest()
+        .map_or(SystemTime::UNIX_EPOCH, |date_time| date_time.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn modules_map() -> HashMap<String, Module> {
+        let mut module = Module::empty();
+        module.id = "module".to_string();
+        module.code = "CS2030S".to_string();
+        module.name = "Programming Methodology II".to_string();
+
+        let mut modules_map = HashMap::new();
+        modules_map.insert(module.id.clone(), module);
+        modules_map
+    }
+
+    fn resource() -> ResourceState {
+        let mut resource = ResourceState::empty();
+        resource.module_id = "module".to_string();
+        resource.path = PathBuf::from("Lectures/Lecture 1.pdf");
+        resource.last_updated =
+            start_of_day(NaiveDate::from_ymd(2021, 8, 10)) + Duration::from_secs(12 * 60 * 60);
+        resource
+    }
+
+    fn filter(messages: Vec<ResourceFilterMessage>) -> ResourceFilter {
+        let mut filter = ResourceFilter::default();
+        for message in messages {
+            filter.update(message);
+        }
+        filter
+    }
+
+    #[test]
+    fn matches_every_term_against_path_or_module() {
+        let modules_map = modules_map();
+        let matches = |query: &str| {
+            filter(vec![ResourceFilterMessage::QueryEdited(query.to_string())])
+                .matches(&resource(), &modules_map)
+        };
+
+        assert!(matches(""));
+        assert!(matches("lecture 1"));
+        assert!(matches("LECTURES cs2030s"));
+        assert!(matches("methodology"));
+        assert!(!matches("lecture tutorial"));
+        assert!(!matches("CS1010"));
+    }
+
+    #[test]
+    fn matches_download_state() {
+        let modules_map = modules_map();
+        let mut downloaded = resource();
+        downloaded.download_path = Some(PathBuf::from("/tmp/Lecture 1.pdf"));
+
+        let downloaded_filter = filter(vec![ResourceFilterMessage::DownloadStateSelected(
+            DownloadState::Downloaded,
+        )]);
+        assert!(downloaded_filter.is_active());
+        assert!(downloaded_filter.matches(&downloaded, &modules_map));
+        assert!(!downloaded_filter.matches(&resource(), &modules_map));
+
+        let not_downloaded_filter = filter(vec![ResourceFilterMessage::DownloadStateSelected(
+            DownloadState::NotDownloaded,
+        )]);
+        assert!(!not_downloaded_filter.matches(&downloaded, &modules_map));
+        assert!(not_downloaded_filter.matches(&resource(), &modules_map));
+    }
+
+    #[test]
+    fn matches_inclusive_date_range() {
+        let modules_map = modules_map();
+        let matches = |from: &str, to: &str| {
+            filter(vec![
+                ResourceFilterMessage::FromEdited(from.to_string()),
+                ResourceFilterMessage::ToEdited(to.to_string()),
+            ])
+            .matches(&resource(), &modules_map)
+        };
+
+        assert!(matches("10/08/2021", "10/08/2021"));
+        assert!(matches("01/08/2021", ""));
+        assert!(matches("", "31/08/2021"));
+        assert!(!matches("11/08/2021", ""));
+        assert!(!matches("", "09/08/2021"));
+        // Dates which can't be parsed are ignored.
+        assert!(matches("next week", ""));
+        assert!(!filter(vec![ResourceFilterMessage::FromEdited(
+            "next week".to_string()
+        )])
+        .is_active());
+    }
+}
@@ -1,15 +1,20 @@
 use iced::{button, Button, Column, Command, Element, Row, Rule, Text};
 
+use crate::data::Data;
 use crate::message::Message;
 use crate::pages::Page;
+use crate::theme::{self, Theme};
 
 #[derive(Debug, Clone)]
 pub struct Header {
+    dashboard_button: button::State,
     modules_button: button::State,
+    announcements_button: button::State,
     files_button: button::State,
     multimedia_button: button::State,
     weblectures_button: button::State,
     conferences_button: button::State,
+    search_button: button::State,
     settings_button: button::State,
 }
 
@@ -21,11 +26,14 @@ pub enum HeaderMessage {
 impl Header {
     pub fn default() -> Self {
         Header {
+            dashboard_button: button::State::new(),
             modules_button: button::State::new(),
+            announcements_button: button::State::new(),
             files_button: button::State::new(),
             multimedia_button: button::State::new(),
             weblectures_button: button::State::new(),
             conferences_button: button::State::new(),
+            search_button: button::State::new(),
             settings_button: button::State::new(),
         }
     }
@@ -38,49 +46,82 @@ impl Header {
         }
     }
 
-    pub fn view(&mut self, active_page: &Page, logged_in: bool) -> Element<HeaderMessage> {
+    pub fn view(
+        &mut self,
+        active_page: &Page,
+        logged_in: bool,
+        data: &Data,
+        theme: Theme,
+    ) -> Element<HeaderMessage> {
         let content = Row::new()
+            .push(create_button(
+                &mut self.dashboard_button,
+                Page::Dashboard,
+                "Dashboard".to_string(),
+                active_page,
+                theme,
+            ))
             .push(create_button(
                 &mut self.modules_button,
                 Page::Modules,
-                "Modules",
+                with_count("Modules", data.modules.unseen_count()),
+                active_page,
+                theme,
+            ))
+            .push(create_button(
+                &mut self.announcements_button,
+                Page::Announcements,
+                with_count("Announcements", data.announcements.unread_count()),
                 active_page,
+                theme,
             ))
             .push(create_button(
                 &mut self.files_button,
                 Page::Files,
-                "Files",
+                with_count("Files", data.files.unseen_count()),
                 active_page,
+                theme,
             ))
             .push(create_button(
                 &mut self.multimedia_button,
                 Page::Multimedia,
-                "Multimedia",
+                with_count("Multimedia", data.multimedia.unseen_count()),
                 active_page,
+                theme,
             ))
             .push(create_button(
                 &mut self.weblectures_button,
                 Page::Weblectures,
-                "Weblectures",
+                with_count("Weblectures", data.weblectures.unseen_count()),
                 active_page,
+                theme,
             ))
             .push(create_button(
                 &mut self.conferences_button,
                 Page::Conferences,
-                "Conferences",
+                with_count("Conferences", data.conferences.unseen_count()),
                 active_page,
+                theme,
+            ))
+            .push(create_button(
+                &mut self.search_button,
+                Page::Search,
+                "Search".to_string(),
+                active_page,
+                theme,
             ))
             .push(create_button(
                 &mut self.settings_button,
                 Page::Settings,
                 // TODO: different color?
-                if logged_in { "Settings" } else { "Settings *" },
+                if logged_in { "Settings" } else { "Settings *" }.to_string(),
                 active_page,
+                theme,
             ));
 
         Column::new()
             .push(content)
-            .push(Rule::horizontal(0).style(style::Divider::Header))
+            .push(Rule::horizontal(0).style(theme::Divider(theme)))
             .into()
     }
 }
@@ -88,62 +129,38 @@ impl Header {
 fn create_button<'a, 'b>(
     button_state: &'a mut button::State,
     page: Page,
-    page_name: &'static str,
+    page_name: String,
     active_page: &'b Page,
+    theme: Theme,
 ) -> Button<'a, HeaderMessage> {
     Button::new(button_state, Text::new(page_name))
-        .style(get_button_style(active_page, &page))
+        .style(get_button_style(active_page, &page, theme))
         .on_press(HeaderMessage::SwitchPage(page))
 }
 
-fn get_button_style(active_page: &Page, current_module: &Page) -> style::Button {
-    if active_page == current_module {
-        style::Button::ActiveButton
+// Appends the number of unseen items to a page name.
+fn with_count(page_name: &str, count: usize) -> String {
+    if count > 0 {
+        format!("{} ({})", page_name, count)
     } else {
-        style::Button::InactiveButton
+        page_name.to_string()
     }
 }
 
-mod style {
-    use iced::{button, rule, Background, Color};
-
-    pub enum Button {
-        ActiveButton,
-        InactiveButton,
-    }
-
-    pub enum Divider {
-        Header,
-    }
-
-    impl button::StyleSheet for Button {
-        fn active(&self) -> button::Style {
-            match self {
-                Button::ActiveButton => button::Style {
-                    background: Option::Some(Background::Color(Color {
-                        r: 0.0,
-                        g: 0.0,
-                        b: 0.545,
-                        a: 1.0,
-                    })),
-                    border_radius: 0.0,
-                    border_width: 0.0,
-                    text_color: Color::WHITE,
-                    ..button::Style::default()
-                },
-                Button::InactiveButton => button::Style::default(),
-            }
-        }
-    }
+fn get_button_style(
+    active_page: &Page,
+    current_module: &Page,
+    theme: Theme,
+) -> Box<dyn button::StyleSheet> {
+    // Module pages are reached from the modules page.
+    let is_active = match (active_page, current_module) {
+        (Page::Module(_), Page::Modules) => true,
+        _ => active_page == current_module,
+    };
 
-    impl rule::StyleSheet for Divider {
-        fn style(&self) -> rule::Style {
-            rule::Style {
-                fill_mode: rule::FillMode::Full,
-                color: Color::BLACK,
-                width: 1,
-                radius: 1.0,
-            }
-        }
+    if is_active {
+        theme::ActiveButton(theme).into()
+    } else {
+        theme::Button(theme).into()
     }
 }
@@ -0,0 +1,191 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::module::Module;
+
+// Where resources are downloaded to within the download location. The placeholders are
+// `{term}`, `{code}` and `{name}` of the module, `{type}` of resource and the `{path}`
+// of the resource within the module.
+pub const DEFAULT_FOLDER_LAYOUT: &str = "{code}/{type}/{path}";
+
+// The path of a resource has to come last, so that the folders within a module can be
+// found by resolving the layout with the path of the folder instead.
+pub fn is_valid_folder_layout(layout: &str) -> bool {
+    let mut components = layout.trim().trim_end_matches('/').split('/').rev();
+    let last = components.next().unwrap_or_default();
+
+    !components.any(|component| component.contains("{path}"))
+        && (last == "{path}" || !last.contains("{path}"))
+}
+
+// Empty or invalid layouts fall back to the default layout.
+fn layout_or_default(layout: &str) -> &str {
+    if layout.trim().is_empty() || !is_valid_folder_layout(layout) {
+        DEFAULT_FOLDER_LAYOUT
+    } else {
+        layout
+    }
+}
+
+// The location of a resource, or of a folder of resources, within the download location.
+pub fn resolve_folder_layout(
+    layout: &str,
+    module: Option<&Module>,
+    type_name: &str,
+    path: &Path,
+) -> PathBuf {
+    let layout = layout_or_default(layout);
+
+    let resolved = PathBuf::from(
+        fill_module_placeholders(layout, module)
+            .replace("{type}", type_name)
+            .replace("{path}", &path.to_string_lossy()),
+    );
+    // Every resource needs a location of its own, so the path is always included.
+    let resolved = if layout.contains("{path}") {
+        resolved
+    } else {
+        resolved.join(path)
+    };
+
+    relative_path(&resolved)
+}
+
+// The folder containing every resource of a module, which is the part of the layout
+// before the type or path of resources. This is the download location itself if the
+// layout starts with the type of resource.
+pub fn resolve_module_folder(layout: &str, module: &Module) -> PathBuf {
+    let layout = layout_or_default(layout);
+
+    let module_layout = layout
+        .split('/')
+        .take_while(|component| !component.contains("{type}") && !component.contains("{path}"))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    relative_path(Path::new(&fill_module_placeholders(
+        &module_layout,
+        Some(module),
+    )))
+}
+
+fn fill_module_placeholders(layout: &str, module: Option<&Module>) -> String {
+    let (term, code, name) = match module {
+        Some(module) => (
+            module.term.as_str(),
+            module.code.as_str(),
+            module.name.as_str(),
+        ),
+        None => ("Unknown", "Unknown", "Unknown"),
+    };
+
+    layout
+        .replace("{term}", &sanitize(term))
+        .replace("{code}", &sanitize(code))
+        .replace("{name}", &sanitize(name))
+}
+
+// Module names can contain slashes, which would otherwise create extra folders, as well
+// as characters which aren't allowed in file names on Windows.
+fn sanitize(value: &str) -> String {
+    value.replace(
+        |c: char| {
+            c.is_control() || matches!(c, '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*')
+        },
+        "-",
+    )
+}
+
+// Keeps the resolved location inside the download location.
+fn relative_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module() -> Module {
+        let mut module = Module::empty();
+        module.term = "2110".to_string();
+        module.code = "CS2030S".to_string();
+        module.name = "Programming: Methodology I/II".to_string();
+        module
+    }
+
+    #[test]
+    fn validates_path_is_last() {
+        assert!(is_valid_folder_layout(DEFAULT_FOLDER_LAYOUT));
+        assert!(is_valid_folder_layout("{term}/{code}/{type}"));
+        assert!(is_valid_folder_layout("{code}/{path}/"));
+        assert!(!is_valid_folder_layout("{code}/{path}/{type}"));
+        assert!(!is_valid_folder_layout("{code}/{type} {path}"));
+    }
+
+    #[test]
+    fn resolves_placeholders() {
+        assert_eq!(
+            resolve_folder_layout(
+                "{term}/{code} {name}/{type}/{path}",
+                Some(&module()),
+                "Files",
+                Path::new("Lectures/Lecture 1.pdf"),
+            ),
+            PathBuf::from(
+                "2110/CS2030S Programming- Methodology I-II/Files/Lectures/Lecture 1.pdf"
+            )
+        );
+        assert_eq!(
+            resolve_folder_layout("{code}", None, "Files", Path::new("Lecture 1.pdf")),
+            PathBuf::from("Unknown/Lecture 1.pdf")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_default_layout() {
+        let path = Path::new("Lectures/Lecture 1.pdf");
+        let expected = PathBuf::from("CS2030S/Files/Lectures/Lecture 1.pdf");
+
+        assert_eq!(
+            resolve_folder_layout("", Some(&module()), "Files", path),
+            expected
+        );
+        assert_eq!(
+            resolve_folder_layout("{path}/{code}", Some(&module()), "Files", path),
+            expected
+        );
+    }
+
+    #[test]
+    fn keeps_resolved_path_inside_download_location() {
+        assert_eq!(
+            resolve_folder_layout(
+                "/../{code}/{path}",
+                Some(&module()),
+                "Files",
+                Path::new("a.pdf")
+            ),
+            PathBuf::from("CS2030S/a.pdf")
+        );
+    }
+
+    #[test]
+    fn resolves_module_folder() {
+        assert_eq!(
+            resolve_module_folder("{term}/{code}/{type}/{path}", &module()),
+            PathBuf::from("2110/CS2030S")
+        );
+        assert_eq!(
+            resolve_module_folder("{type}/{code}/{path}", &module()),
+            PathBuf::new()
+        );
+    }
+
+    #[test]
+    fn sanitizes_invalid_characters() {
+        assert_eq!(sanitize(r#"a/b\c<d>e:f"g|h?i*j"#), "a-b-c-d-e-f-g-h-i-j");
+        assert_eq!(sanitize("tab\tnewline\n"), "tab-newline-");
+        assert_eq!(sanitize("CS2030S"), "CS2030S");
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use tokio::io::AsyncReadExt;
+
+use crate::module::Module;
+use crate::resource::{ResourceState, ResourceType};
+
+// Snapshot of a resource's recorded download state, along with the location it
+// would be downloaded to, so that the local file system can be checked in a Command.
+#[derive(Debug, Clone)]
+pub struct LocalResource {
+    pub module_id: String,
+    pub path: PathBuf,
+    pub download_path: Option<PathBuf>,
+    pub download_time: Option<SystemTime>,
+    pub download_hash: Option<String>,
+    pub download_size: Option<u64>,
+    local_path: PathBuf,
+    // The download state when the snapshot was taken, which is left untouched.
+    recorded: (Option<PathBuf>, Option<SystemTime>, Option<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct FileInfo {
+    pub hash: String,
+    pub size: u64,
+}
+
+// A local file which has been moved aside so that a newer version can be downloaded.
+#[derive(Debug, Clone)]
+pub struct SetAsideFile {
+    pub path: PathBuf,
+    pub modified: bool,
+}
+
+pub fn collect_local_resources<'a>(
+    resources: impl Iterator<Item = &'a ResourceState>,
+    modules_map: &HashMap<String, Module>,
+    resource_type: ResourceType,
+    download_dir: &Path,
+    folder_layout: &str,
+) -> Vec<LocalResource> {
+    resources
+        .map(|resource| LocalResource {
+            module_id: resource.module_id.clone(),
+            path: resource.path.clone(),
+            download_path: resource.download_path.clone(),
+            download_time: resource.download_time,
+            download_hash: resource.download_hash.clone(),
+            download_size: resource.download_size,
+            local_path: download_dir.join(resource.local_resource_path(
+                modules_map,
+                resource_type,
+                folder_layout,
+            )),
+            recorded: (
+                resource.download_path.clone(),
+                resource.download_time,
+                resource.download_hash.clone(),
+            ),
+        })
+        .collect()
+}
+
+impl LocalResource {
+    // Whether the resource still has the download state it had when the snapshot was
+    // taken. Otherwise it has been downloaded or deleted since, and the snapshot is stale.
+    pub fn is_current(&self, resource: &ResourceState) -> bool {
+        let (download_path, download_time, download_hash) = &self.recorded;
+
+        resource.download_path == *download_path
+            && resource.download_time == *download_time
+            && resource.download_hash == *download_hash
+    }
+}
+
+// Compares the recorded download state of each resource with the local file system,
+// returning only the resources whose download state has changed.
+pub async fn reconcile_local_resources(resources: Vec<LocalResource>) -> Vec<LocalResource> {
+    let mut changed = vec![];
+
+    for mut resource in resources {
+        let recorded_path = match &resource.download_path {
+            Some(download_path) if download_path.is_absolute() => Some(download_path.clone()),
+            // Older versions recorded the resource path instead of the full path to the
+            // downloaded file, so we check the expected location instead.
+            Some(_) => Some(resource.local_path.clone()),
+            None => None,
+        };
+
+        let (download_path, download_time, download_hash, download_size) = match recorded_path {
+            Some(recorded_path) if file_modified(&recorded_path).await.is_some() => (
+                Some(recorded_path),
+                resource.download_time,
+                resource.download_hash.clone(),
+                resource.download_size,
+            ),
+            // Either the file was never recorded, or it has been deleted or moved. Adopt
+            // any file which already exists at the expected location. Since we don't know
+            // what was downloaded, no hash is recorded for adopted files.
+            _ => match file_modified(&resource.local_path).await {
+                Some(modified) => (
+                    Some(resource.local_path.clone()),
+                    Some(modified),
+                    None,
+                    None,
+                ),
+                None => (None, None, None, None),
+            },
+        };
+
+        if download_path != resource.download_path
+            || download_time != resource.download_time
+            || download_hash != resource.download_hash
+        {
+            resource.download_path = download_path;
+            resource.download_time = download_time;
+            resource.download_hash = download_hash;
+            resource.download_size = download_size;
+            changed.push(resource);
+        }
+    }
+
+    changed
+}
+
+// Moves the local copies of resources to the location given when collecting them,
+// returning the resources which were moved successfully.
+pub async fn archive_local_resources(resources: Vec<LocalResource>) -> Vec<LocalResource> {
+    let mut archived = vec![];
+
+    for mut resource in resources {
+        if let Some(download_path) = &resource.download_path {
+            if move_file(download_path, &resource.local_path).await.is_ok() {
+                resource.download_path = Some(resource.local_path.clone());
+                archived.push(resource);
+            }
+        }
+    }
+
+    archived
+}
+
+// Deletes the local copies of resources, returning the resources which no longer have one,
+// along with the local copies which were kept. Like when updating files, local copies
+// which don't match the recorded hash are assumed to have been modified and are kept.
+pub async fn delete_local_resources(
+    resources: Vec<LocalResource>,
+) -> (Vec<LocalResource>, Vec<PathBuf>) {
+    let mut deleted = vec![];
+    let mut kept = vec![];
+
+    for mut resource in resources {
+        if let Some(download_path) = resource.download_path.clone() {
+            let removed = match read_file_info(&download_path).await {
+                Some(info) if Some(info.hash) == resource.download_hash => {
+                    tokio::fs::remove_file(&download_path).await.is_ok()
+                }
+                // The local copy is already gone, so only the recorded state is cleared.
+                None if file_modified(&download_path).await.is_none() => true,
+                _ => {
+                    kept.push(download_path);
+                    false
+                }
+            };
+
+            if removed {
+                resource.download_path = None;
+                resource.download_time = None;
+                resource.download_hash = None;
+                resource.download_size = None;
+                deleted.push(resource);
+            }
+        }
+    }
+
+    (deleted, kept)
+}
+
+async fn move_file(from: &Path, to: &Path) -> Result<(), std::io::Error> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    // Renaming fails across file systems, so fall back to copying the file instead.
+    if tokio::fs::rename(from, to).await.is_err() {
+        tokio::fs::copy(from, to).await?;
+        tokio::fs::remove_file(from).await?;
+    }
+
+    Ok(())
+}
+
+async fn file_modified(path: &Path) -> Option<SystemTime> {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_file() => {
+            Some(metadata.modified().unwrap_or_else(|_| SystemTime::now()))
+        }
+        _ => None,
+    }
+}
+
+pub async fn read_file_info(path: &Path) -> Option<FileInfo> {
+    let mut file = tokio::fs::File::open(path).await.ok()?;
+    let mut hasher = sha1::Sha1::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut size = 0;
+
+    loop {
+        let read = file.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    Some(FileInfo {
+        hash: hasher.digest().to_string(),
+        size,
+    })
+}
+
+// Moves an existing local file out of the way before a newer version is downloaded
+// to the same location. The file is checked against the hash recorded when it was
+// downloaded, and files without a recorded hash are assumed to have been modified.
+pub async fn set_aside_file(
+    path: &Path,
+    recorded_hash: Option<String>,
+    version: Option<SystemTime>,
+) -> Result<Option<SetAsideFile>, std::io::Error> {
+    if file_modified(path).await.is_none() {
+        return Ok(None);
+    }
+
+    let modified = match (read_file_info(path).await, recorded_hash) {
+        (Some(info), Some(recorded_hash)) => info.hash != recorded_hash,
+        _ => true,
+    };
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut file_name = match version {
+        Some(version) if !modified => {
+            let version: DateTime<Local> = version.into();
+            format!("{} (version {})", stem, version.format("%Y-%m-%d %H%M%S"))
+        }
+        _ => {
+            let now: DateTime<Local> = SystemTime::now().into();
+            format!("{} (local copy {})", stem, now.format("%Y-%m-%d %H%M%S"))
+        }
+    };
+    if let Some(extension) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+    let set_aside_path = path.with_file_name(file_name);
+
+    tokio::fs::rename(path, &set_aside_path).await?;
+
+    Ok(Some(SetAsideFile {
+        path: set_aside_path,
+        modified,
+    }))
+}
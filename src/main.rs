@@ -1,23 +1,37 @@
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use iced::{executor, Application, Clipboard, Column, Command, Element, Settings};
+use iced::{
+    executor, keyboard, time, Application, Clipboard, Column, Command, Container, Element, Length,
+    Settings, Subscription,
+};
+use iced_native::{event, Event};
 
 use futures_util::future;
 
 use fluminurs::Api;
 
+mod announcement;
 mod api;
+mod confirmation;
 mod data;
+mod filter;
 mod header;
+mod layout;
+mod local;
 mod message;
 mod module;
+mod opener;
 mod pages;
 mod resource;
 mod settings;
+mod sort;
+mod status_bar;
 mod storage;
+mod theme;
 mod utils;
 
+use crate::confirmation::DeleteConfirmation;
 use crate::data::Data;
 use crate::header::Header;
 use crate::message::{handle_message, Message};
@@ -25,6 +39,7 @@ use crate::module::Module;
 use crate::pages::{Page, Pages};
 use crate::resource::ResourceType;
 use crate::settings::Settings as FluminursDesktopSettings;
+use crate::status_bar::{BackgroundTasks, StatusBar};
 use crate::storage::Storage;
 
 pub fn main() -> iced::Result {
@@ -39,6 +54,10 @@ pub struct FluminursDesktop {
     current_page: Page,
     pages: Pages,
     header: Header,
+    status_bar: StatusBar,
+    delete_confirmation: DeleteConfirmation,
+    username: Option<String>,
+    modifiers: keyboard::Modifiers,
 }
 
 #[derive(Debug, Clone)]
@@ -51,9 +70,13 @@ impl FluminursDesktop {
             settings: FluminursDesktopSettings::default(),
             data: Data::default(),
             modules_map: HashMap::new(),
-            current_page: Page::Login,
+            current_page: Page::Loading,
             pages: Pages::default(),
             header: Header::default(),
+            status_bar: StatusBar::default(),
+            delete_confirmation: DeleteConfirmation::default(),
+            username: None,
+            modifiers: keyboard::Modifiers::default(),
         }
     }
 }
@@ -78,71 +101,165 @@ impl Application for FluminursDesktop {
             Page::Loading => String::from("fluminurs-desktop"),
             Page::Login => String::from("Login"),
             Page::Settings => String::from("Settings"),
+            Page::Dashboard => String::from("Dashboard"),
             Page::Modules => String::from("Modules"),
+            Page::Module(ref module_id) => match self.modules_map.get(module_id) {
+                Some(module) => module.code.clone(),
+                None => String::from("Module"),
+            },
+            Page::Announcements => String::from("Announcements"),
             Page::Files => String::from("Files"),
             Page::Multimedia => String::from("Multimedia"),
             Page::Weblectures => String::from("Weblectures"),
             Page::Conferences => String::from("Conferences"),
+            Page::Search => String::from("Search"),
         }
     }
 
     fn update(
         &mut self,
         message: Self::Message,
-        _clipboard: &mut Clipboard,
+        clipboard: &mut Clipboard,
     ) -> Command<Self::Message> {
-        handle_message(self, message)
+        handle_message(self, message, clipboard)
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let events = iced_native::subscription::events_with(|event, status| match event {
+            // Keep track of the modifier keys, which change how resources are selected.
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            // Text inputs capture every key press while focused, but tab is still needed
+            // to move between them.
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if key_code == keyboard::KeyCode::Tab || status == event::Status::Ignored => {
+                Some(Message::KeyPressed((key_code, modifiers)))
+            }
+            _ => None,
+        });
+
+        // Refresh the time since the last refresh shown in the status bar.
+        let tick = time::every(Duration::from_secs(60)).map(|_| Message::Tick(()));
+
+        Subscription::batch(vec![events, tick])
     }
 
     fn view(&mut self) -> Element<Self::Message> {
-        let display_header = self.current_page != Page::Login;
+        let display_header = !matches!(self.current_page, Page::Loading | Page::Login);
         let logged_in = self.api.is_some();
         let has_data = self.data.modules.last_updated != SystemTime::UNIX_EPOCH;
+        let theme = self.settings.get_theme();
+        let tasks = BackgroundTasks {
+            signing_in: self.pages.login.is_signing_in() || self.pages.loading.is_signing_in(),
+            saving: self.data.is_saving() || self.settings.is_saving(),
+        };
 
         let page = match self.current_page {
-            Page::Loading => self.pages.loading.view().map(Message::LoadingPage),
-            Page::Login => self.pages.login.view(has_data).map(Message::LoginPage),
+            Page::Loading => self.pages.loading.view(theme).map(Message::LoadingPage),
+            Page::Login => self
+                .pages
+                .login
+                .view(has_data, self.settings.get_save_password(), theme)
+                .map(Message::LoginPage),
             Page::Settings => self
                 .pages
                 .settings
                 .view(&mut self.settings, logged_in)
                 .map(Message::SettingsPage),
+            Page::Dashboard => self
+                .pages
+                .dashboard
+                .view(&self.data, &self.modules_map, theme)
+                .map(Message::DashboardPage),
             Page::Modules => self
                 .pages
                 .modules
-                .view(&self.data.modules)
+                .view(&mut self.data, theme)
                 .map(Message::ModulesPage),
+            Page::Module(ref module_id) => self
+                .pages
+                .module
+                .view(module_id, &self.data, &self.modules_map, theme)
+                .map(Message::ModulePage),
+            Page::Announcements => self
+                .pages
+                .announcements
+                .view(&self.data.announcements, &self.modules_map, theme)
+                .map(Message::AnnouncementsPage),
             Page::Files => self
                 .pages
                 .files
-                .view(&mut self.data.files, &self.modules_map)
+                .view(
+                    &self.data.files,
+                    &self.modules_map,
+                    self.settings.get_resource_sort(ResourceType::File),
+                    theme,
+                )
                 .map(|message| Message::ResourcesPage((ResourceType::File, message))),
             Page::Multimedia => self
                 .pages
                 .multimedia
-                .view(&mut self.data.multimedia, &self.modules_map)
+                .view(
+                    &self.data.multimedia,
+                    &self.modules_map,
+                    self.settings.get_resource_sort(ResourceType::Multimedia),
+                    theme,
+                )
                 .map(|message| Message::ResourcesPage((ResourceType::Multimedia, message))),
             Page::Weblectures => self
                 .pages
                 .weblectures
-                .view(&mut self.data.weblectures, &self.modules_map)
+                .view(
+                    &self.data.weblectures,
+                    &self.modules_map,
+                    self.settings.get_resource_sort(ResourceType::Weblecture),
+                    theme,
+                )
                 .map(|message| Message::ResourcesPage((ResourceType::Weblecture, message))),
             Page::Conferences => self
                 .pages
                 .conferences
-                .view(&mut self.data.conferences, &self.modules_map)
+                .view(
+                    &self.data.conferences,
+                    &self.modules_map,
+                    self.settings.get_resource_sort(ResourceType::Conference),
+                    theme,
+                )
                 .map(|message| Message::ResourcesPage((ResourceType::Conference, message))),
+            Page::Search => self
+                .pages
+                .search
+                .view(&self.data, &self.modules_map, theme)
+                .map(Message::SearchPage),
         };
 
-        if display_header {
+        let content: Element<_> = if display_header {
             let header = self
                 .header
-                .view(&self.current_page, logged_in)
+                .view(&self.current_page, logged_in, &self.data, theme)
                 .map(Message::Header);
+            let status_bar = self
+                .status_bar
+                .view(&self.data, self.username.as_deref(), tasks, theme)
+                .map(Message::StatusBar);
+            let bars = Column::new().push(header).push(status_bar);
+            let bars = match self.delete_confirmation.view(theme) {
+                Some(confirmation) => bars.push(confirmation.map(Message::DeleteConfirmation)),
+                None => bars,
+            };
 
-            Column::new().spacing(20).push(header).push(page).into()
+            Column::new().spacing(20).push(bars).push(page).into()
         } else {
             page
-        }
+        };
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::Container(theme))
+            .into()
     }
 }
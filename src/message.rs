@@ -1,23 +1,43 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use iced::Command;
+use iced::{keyboard, Clipboard, Command};
+
+use futures_util::future;
 
 use fluminurs::Api;
 
-use crate::api;
+use crate::announcement::Announcement;
+use crate::api::{self, DownloadedResource, LoginError};
+use crate::confirmation::DeleteConfirmationMessage;
 use crate::data::{Data, DataItems, FetchStatus};
 use crate::header::HeaderMessage;
+use crate::layout::resolve_module_folder;
+use crate::local::{
+    archive_local_resources, collect_local_resources, delete_local_resources,
+    reconcile_local_resources, LocalResource,
+};
 use crate::module::{Module, ModuleMessage};
-use crate::pages::loading::LoadingMessage;
+use crate::opener::{open_file, open_path, OpenerRuleEdit};
+use crate::pages::announcements::AnnouncementsMessage;
+use crate::pages::dashboard::DashboardMessage;
+use crate::pages::loading::{LoadingMessage, LoadingPage, LoadingState, LoadingStep};
 use crate::pages::login::LoginMessage;
-use crate::pages::resources::{ResourcesMessage, ResourcesPage};
+use crate::pages::module::ModulePageMessage;
+use crate::pages::resources::{BatchAction, ResourcesMessage, ResourcesPage};
+use crate::pages::search::SearchMessage;
 use crate::pages::settings::SettingsMessage;
 use crate::pages::Page;
 use crate::resource::{ResourceMessage, ResourceState, ResourceType};
 use crate::settings::{default_download_dir, Settings};
+use crate::sort::ResourceSort;
+use crate::status_bar::StatusBarMessage;
 use crate::storage::{Storage, StorageWrite};
-use crate::utils::{clean_username, construct_modules_map, merge_modules, merge_resources};
+use crate::theme::Theme;
+use crate::utils::{
+    clean_username, construct_modules_map, merge_announcements, merge_modules, merge_resources,
+};
 use crate::Error;
 use crate::FluminursDesktop;
 
@@ -26,45 +46,112 @@ pub enum Message {
     LoadingPage(LoadingMessage),
     LoginPage(LoginMessage),
     SettingsPage(SettingsMessage),
+    DashboardPage(DashboardMessage),
     ModulesPage(ModuleMessage),
+    ModulePage(ModulePageMessage),
+    AnnouncementsPage(AnnouncementsMessage),
     ResourcesPage((ResourceType, ResourcesMessage)),
+    SearchPage(SearchMessage),
     Header(HeaderMessage),
+    StatusBar(StatusBarMessage),
     SwitchPage(Page),
+    Tick(()),
 
     // Settings
     ToggleSaveUsername(bool),
     ToggleSavePassword(bool),
+    ToggleKeepPreviousVersions(bool),
     ChangeDownloadLocation(()),
+    ResourceSortChanged((ResourceType, ResourceSort)),
+    ThemeChanged(Theme),
+    OpenerRulesEdited(OpenerRuleEdit),
+    FolderLayoutChanged(String),
+    ModifiersChanged(keyboard::Modifiers),
+    KeyPressed((keyboard::KeyCode, keyboard::Modifiers)),
     DownloadLocationChanged(PathBuf),
 
-    Startup((Result<Settings, Error>, Result<Data, Error>)),
+    Startup((Result<Option<Settings>, Error>, Result<Option<Data>, Error>)),
+    RetryStartup(()),
     SettingsSaved(Result<StorageWrite, Error>),
     DataSaved(Result<StorageWrite, Error>),
-    LoadedAPI(Result<(Api, String, String, DataItems<Module>), Error>),
+    LoadedAPI(Result<(Api, String, String, DataItems<Module>), LoginError>),
     LoadModules(()),
     LoadedModules(Result<DataItems<Module>, Error>),
     LoadResources(ResourceType),
-    LoadedResources((ResourceType, Result<DataItems<ResourceState>, Error>)),
+    LoadModuleResources(String),
+    LoadAnnouncements(()),
+    LoadedAnnouncements(Result<(DataItems<Announcement>, Vec<String>), Error>),
+    MarkAnnouncementsRead(()),
+    ToggleAnnouncementRead(String),
+    LoadedResources(
+        (
+            ResourceType,
+            Option<String>,
+            Result<(DataItems<ResourceState>, Vec<String>), Error>,
+        ),
+    ),
     ResourceMessage((ResourceType, String, PathBuf, ResourceMessage)),
-    ResourceDownloaded((ResourceType, String, PathBuf, Result<PathBuf, Error>)),
-    OpenFileResult(Result<std::process::ExitStatus, std::io::Error>),
+    ResourceDownloaded(
+        (
+            ResourceType,
+            String,
+            PathBuf,
+            Result<DownloadedResource, Error>,
+        ),
+    ),
+    ReconcileResources(ResourceType),
+    ReconciledResources((ResourceType, Vec<LocalResource>)),
+    DeleteConfirmation(DeleteConfirmationMessage),
+    DeleteLocalResources((ResourceType, Vec<(String, PathBuf)>)),
+    DeletedLocalResources((ResourceType, (Vec<LocalResource>, Vec<PathBuf>))),
+    MarkSeen(Option<ResourceType>),
+    ArchiveRemovedResources(ResourceType),
+    DownloadFolder((ResourceType, String, PathBuf)),
+    OpenFolder((ResourceType, String, PathBuf)),
+    SelectResource((ResourceType, String, PathBuf, bool)),
+    SelectAllResources((ResourceType, bool)),
+    BatchAction((ResourceType, BatchAction)),
+    RetryFailedDownloads(()),
+    SyncModule(String),
+    OpenModuleFolder(String),
+    OpenFileResult(Result<(), std::io::Error>),
 }
 
-pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command<Message> {
+pub fn handle_message(
+    state: &mut FluminursDesktop,
+    message: Message,
+    clipboard: &mut Clipboard,
+) -> Command<Message> {
     match message {
         // For messages that have to deal with local state, pass them back to
         // be handled by each individual page/component.
         Message::LoadingPage(message) => state.pages.loading.update(message),
         Message::LoginPage(message) => state.pages.login.update(message),
         Message::SettingsPage(message) => state.pages.settings.update(message),
+        Message::DashboardPage(message) => state.pages.dashboard.update(message),
         Message::ModulesPage(message) => state.pages.modules.update(message),
+        Message::ModulePage(message) => state.pages.module.update(message),
+        Message::AnnouncementsPage(message) => state.pages.announcements.update(message),
         Message::ResourcesPage((resource_type, message)) => {
             get_resources_page(state, resource_type).update(message)
         }
+        Message::SearchPage(message) => state.pages.search.update(message),
         Message::Header(message) => state.header.update(message),
+        Message::DeleteConfirmation(message) => state.delete_confirmation.update(message),
+        Message::StatusBar(message) => state.status_bar.update(message),
+
+        // Nothing needs to be done, other than displaying the current time again.
+        Message::Tick(()) => Command::none(),
 
         // Switch the current active page.
         Message::SwitchPage(page) => {
+            // Keep track of the page which opened the login page, so that we can return to it.
+            if page == Page::Login && state.current_page != Page::Login {
+                state
+                    .pages
+                    .login
+                    .set_previous_page(state.current_page.clone());
+            }
             state.current_page = page;
             Command::none()
         }
@@ -78,69 +165,152 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
             state.settings.set_save_password(save_password);
             Command::perform(state.settings.save(), Message::SettingsSaved)
         }
+        Message::ToggleKeepPreviousVersions(keep_previous_versions) => {
+            state
+                .settings
+                .set_keep_previous_versions(keep_previous_versions);
+            Command::perform(state.settings.save(), Message::SettingsSaved)
+        }
+        Message::ModifiersChanged(modifiers) => {
+            state.modifiers = modifiers;
+            Command::none()
+        }
+        Message::KeyPressed((key_code, modifiers)) => handle_key_press(state, key_code, modifiers),
+        Message::ThemeChanged(theme) => {
+            state.settings.set_theme(theme);
+            Command::perform(state.settings.save(), Message::SettingsSaved)
+        }
+        Message::OpenerRulesEdited(edit) => {
+            state.settings.edit_opener_rules(edit);
+            Command::perform(state.settings.save(), Message::SettingsSaved)
+        }
+        Message::FolderLayoutChanged(folder_layout) => {
+            state.settings.set_folder_layout(folder_layout);
+            Command::perform(state.settings.save(), Message::SettingsSaved)
+        }
+        Message::ResourceSortChanged((resource_type, sort)) => {
+            state.settings.set_resource_sort(resource_type, sort);
+            Command::perform(state.settings.save(), Message::SettingsSaved)
+        }
 
         Message::Startup((settings, data)) => {
-            // Once local data exists, skip the login page and display it directly.
-            let has_data = match data {
-                Ok(data) => {
+            let settings_state = match settings {
+                Ok(Some(settings)) => {
+                    state.settings = settings;
+                    LoadingState::Done
+                }
+                Ok(None) => LoadingState::Done,
+                Err(_) => LoadingState::Failed(
+                    "Settings could not be read, so the default settings will be used".to_string(),
+                ),
+            };
+            let (data_state, has_data) = match data {
+                Ok(Some(data)) => {
                     state.data = data;
-                    state.current_page = Page::Modules;
-
-                    true
+                    state.modules_map = construct_modules_map(&state.data.modules.items);
+                    (LoadingState::Done, true)
                 }
-                Err(_) => false,
+                Ok(None) => (LoadingState::Done, false),
+                Err(_) => (
+                    LoadingState::Failed("Saved data could not be read".to_string()),
+                    false,
+                ),
             };
+            let failed = matches!(settings_state, LoadingState::Failed(_))
+                || matches!(data_state, LoadingState::Failed(_));
 
-            match settings {
-                Ok(settings) => {
-                    state.settings = settings;
+            let loading = &mut state.pages.loading;
+            loading.update(LoadingMessage::StepChanged(
+                LoadingStep::Settings,
+                settings_state,
+            ));
+            loading.update(LoadingMessage::StepChanged(LoadingStep::Data, data_state));
 
-                    let has_username = if let Some(username) = state.settings.get_username() {
-                        state
-                            .pages
-                            .login
-                            .update(LoginMessage::UsernameEdited(username.to_string()));
+            let has_username = if let Some(username) = state.settings.get_username() {
+                state
+                    .pages
+                    .login
+                    .update(LoginMessage::UsernameEdited(username.to_string()));
 
-                        true
-                    } else {
-                        false
-                    };
-                    let has_password = if let Some(password) = state.settings.get_password() {
-                        state
-                            .pages
-                            .login
-                            .update(LoginMessage::PasswordEdited(password.to_string()));
+                true
+            } else {
+                false
+            };
+            let has_password = if let Some(password) = state.settings.get_password() {
+                state
+                    .pages
+                    .login
+                    .update(LoginMessage::PasswordEdited(password.to_string()));
 
-                        true
-                    } else {
-                        false
-                    };
+                true
+            } else {
+                false
+            };
 
-                    if !has_data {
-                        state.current_page = Page::Login;
-                        Command::none()
-                    } else if has_username && has_password {
-                        Command::perform(
-                            api::login(
-                                clean_username(&state.settings.get_username().as_ref().unwrap()),
-                                state
-                                    .settings
-                                    .get_password()
-                                    .as_ref()
-                                    .cloned()
-                                    .unwrap()
-                                    .to_string(),
-                            ),
-                            Message::LoadedAPI,
-                        )
-                    } else {
-                        Command::none()
-                    }
-                }
-                Err(_) => Command::none(),
+            // Stay on the loading page if anything went wrong, so that the user can decide
+            // whether to retry. Once local data exists, skip the login page and display it
+            // directly, signing in with the saved login details in the background.
+            let command = if failed {
+                state.pages.loading.update(LoadingMessage::StepChanged(
+                    LoadingStep::Login,
+                    LoadingState::Skipped,
+                ))
+            } else if !has_data {
+                state.current_page = Page::Login;
+                Command::none()
+            } else if has_username && has_password {
+                state.pages.loading.update(LoadingMessage::StepChanged(
+                    LoadingStep::Login,
+                    LoadingState::InProgress,
+                ));
+                state.current_page = Page::Dashboard;
+                Command::perform(
+                    api::login(
+                        clean_username(&state.settings.get_username().as_ref().unwrap()),
+                        state
+                            .settings
+                            .get_password()
+                            .as_ref()
+                            .cloned()
+                            .unwrap()
+                            .to_string(),
+                    ),
+                    Message::LoadedAPI,
+                )
+            } else {
+                state.pages.loading.update(LoadingMessage::StepChanged(
+                    LoadingStep::Login,
+                    LoadingState::Skipped,
+                ));
+                state.current_page = Page::Dashboard;
+                Command::none()
+            };
+
+            // Check that previously downloaded files still exist, now that we know the
+            // download location.
+            if has_data {
+                Command::batch(vec![
+                    command,
+                    reconcile_resources(state, ResourceType::File),
+                    reconcile_resources(state, ResourceType::Multimedia),
+                    reconcile_resources(state, ResourceType::Weblecture),
+                    reconcile_resources(state, ResourceType::Conference),
+                ])
+            } else {
+                command
             }
         }
 
+        Message::RetryStartup(()) => {
+            state.pages.loading = LoadingPage::default();
+            state.current_page = Page::Loading;
+
+            Command::perform(
+                async { future::join(Settings::load(), Data::load()).await },
+                Message::Startup,
+            )
+        }
+
         Message::SettingsSaved(message) => match message {
             Ok(StorageWrite::Successful) => {
                 println!("Saved settings");
@@ -154,7 +324,9 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
             Ok(StorageWrite::Unnecessary) => Command::none(),
             // TODO
             Err(_) => {
-                println!("Failed to save settings");
+                state
+                    .status_bar
+                    .set_error("Failed to save settings".to_string());
                 Command::none()
             }
         },
@@ -172,7 +344,9 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
             Ok(StorageWrite::Unnecessary) => Command::none(),
             // TODO
             Err(_) => {
-                println!("Failed to save data");
+                state
+                    .status_bar
+                    .set_error("Failed to save data".to_string());
                 Command::none()
             }
         },
@@ -181,10 +355,28 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
         Message::LoadedAPI(result) => match result {
             Ok((api, username, password, modules)) => {
                 state.api = Some(api);
+                state.username = Some(username.clone());
+                state.pages.login.update(LoginMessage::Succeeded);
                 merge_modules(&mut state.data.modules, modules);
                 state.data.mark_dirty();
                 state.modules_map = construct_modules_map(&state.data.modules.items);
-                state.current_page = Page::Modules;
+                state.pages.loading.update(LoadingMessage::StepChanged(
+                    LoadingStep::Login,
+                    LoadingState::Done,
+                ));
+                // Don't move away from saved data which is already being browsed.
+                match state.current_page {
+                    Page::Loading => state.current_page = Page::Dashboard,
+                    Page::Login => {
+                        state.current_page = state
+                            .pages
+                            .login
+                            .take_previous_page()
+                            .filter(|page| *page != Page::Loading)
+                            .unwrap_or(Page::Dashboard);
+                    }
+                    _ => {}
+                }
 
                 state.settings.set_login_details(username, password);
 
@@ -195,9 +387,20 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
                     Command::perform(async { ResourceType::Multimedia }, Message::LoadResources),
                     Command::perform(async { ResourceType::Weblecture }, Message::LoadResources),
                     Command::perform(async { ResourceType::Conference }, Message::LoadResources),
+                    Command::perform(async {}, Message::LoadAnnouncements),
                 ])
             }
-            Err(_) => state.pages.login.update(LoginMessage::Failed),
+            Err(error) => {
+                state.status_bar.set_error(error.to_string());
+                if state.current_page == Page::Loading {
+                    state.pages.loading.update(LoadingMessage::StepChanged(
+                        LoadingStep::Login,
+                        LoadingState::Failed(error.to_string()),
+                    ))
+                } else {
+                    state.pages.login.update(LoginMessage::Failed(error))
+                }
+            }
         },
 
         // Load modules.
@@ -231,65 +434,26 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
             // TODO
             Err(_) => {
                 state.data.modules.fetch_status = FetchStatus::Error;
+                state
+                    .status_bar
+                    .set_error("Failed to load modules".to_string());
 
                 Command::none()
             }
         },
 
         // Load resources.
-        Message::LoadResources(resource_type) => {
-            match state.api.as_ref().cloned() {
-                Some(api) => {
-                    if state.data.modules.items.len() > 0 {
-                        let modules = state
-                            .data
-                            .modules
-                            .items
-                            .iter()
-                            .cloned()
-                            .filter_map(|module| module.internal_module)
-                            .collect();
-                        let last_updated = SystemTime::now();
-                        let fetch_status = get_fetch_status(state, resource_type);
-                        *fetch_status = FetchStatus::Fetching;
-
-                        Command::perform(
-                            async move {
-                                let result = match resource_type {
-                                    ResourceType::File => {
-                                        api::load_modules_files(api, modules, last_updated).await
-                                    }
-                                    ResourceType::Multimedia => {
-                                        api::load_modules_multimedia(api, modules, last_updated)
-                                            .await
-                                    }
-                                    ResourceType::Weblecture => {
-                                        api::load_modules_weblectures(api, modules, last_updated)
-                                            .await
-                                    }
-                                    ResourceType::Conference => {
-                                        api::load_modules_conferences(api, modules, last_updated)
-                                            .await
-                                    }
-                                };
-
-                                (resource_type, result)
-                            },
-                            Message::LoadedResources,
-                        )
-                    } else {
-                        // No modules
-                        Command::none()
-                    }
-                }
-                // TODO: refresh API?
-                None => Command::none(),
-            }
-        }
+        Message::LoadResources(resource_type) => load_resources(state, resource_type, None),
+        Message::LoadModuleResources(module_id) => Command::batch(vec![
+            load_resources(state, ResourceType::File, Some(&module_id)),
+            load_resources(state, ResourceType::Multimedia, Some(&module_id)),
+            load_resources(state, ResourceType::Weblecture, Some(&module_id)),
+            load_resources(state, ResourceType::Conference, Some(&module_id)),
+        ]),
 
         // Update loaded resources.
-        Message::LoadedResources((resource_type, result)) => match result {
-            Ok(resources) => {
+        Message::LoadedResources((resource_type, module_id, result)) => match result {
+            Ok((mut resources, loaded_module_ids)) => {
                 let curr_resources = match resource_type {
                     ResourceType::File => &mut state.data.files,
                     ResourceType::Multimedia => &mut state.data.multimedia,
@@ -297,20 +461,106 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
                     ResourceType::Conference => &mut state.data.conferences,
                 };
 
-                merge_resources(curr_resources, resources);
+                // Refreshing a single module leaves the other modules as they were, so the
+                // resource type keeps its last updated time and fetch status.
+                if module_id.is_some() {
+                    resources.last_updated = curr_resources.last_updated;
+                    resources.fetch_status = curr_resources.fetch_status.clone();
+                }
+
+                merge_resources(curr_resources, resources, &loaded_module_ids);
                 state.data.mark_dirty();
 
                 Command::perform(state.data.save(), Message::DataSaved)
             }
             // TODO
             Err(_) => {
-                let fetch_status = get_fetch_status(state, resource_type);
-                *fetch_status = FetchStatus::Error;
+                match module_id.and_then(|module_id| state.modules_map.get(&module_id)) {
+                    Some(module) => state.status_bar.set_error(format!(
+                        "Failed to load {} of {}",
+                        resource_type.name().to_lowercase(),
+                        module.code
+                    )),
+                    None => {
+                        let fetch_status = get_fetch_status(state, resource_type);
+                        *fetch_status = FetchStatus::Error;
+                        state.status_bar.set_error(format!(
+                            "Failed to load {}",
+                            resource_type.name().to_lowercase()
+                        ));
+                    }
+                }
 
                 Command::none()
             }
         },
 
+        // Load announcements of every module.
+        Message::LoadAnnouncements(()) => match state.api.as_ref().cloned() {
+            Some(api) => {
+                let modules = state
+                    .data
+                    .modules
+                    .items
+                    .iter()
+                    .cloned()
+                    .filter_map(|module| module.internal_module)
+                    .collect();
+                let last_updated = SystemTime::now();
+                state.data.announcements.fetch_status = FetchStatus::Fetching;
+
+                Command::perform(
+                    api::load_modules_announcements(api, modules, last_updated),
+                    Message::LoadedAnnouncements,
+                )
+            }
+            // TODO: refresh API?
+            None => Command::none(),
+        },
+
+        Message::LoadedAnnouncements(result) => match result {
+            Ok((announcements, loaded_module_ids)) => {
+                merge_announcements(
+                    &mut state.data.announcements,
+                    announcements,
+                    &loaded_module_ids,
+                );
+                state.data.mark_dirty();
+
+                Command::perform(state.data.save(), Message::DataSaved)
+            }
+            Err(_) => {
+                state.data.announcements.fetch_status = FetchStatus::Error;
+                state
+                    .status_bar
+                    .set_error("Failed to load announcements".to_string());
+
+                Command::none()
+            }
+        },
+
+        Message::MarkAnnouncementsRead(()) => {
+            state.data.announcements.mark_all_read();
+            state.data.mark_dirty();
+
+            Command::perform(state.data.save(), Message::DataSaved)
+        }
+
+        Message::ToggleAnnouncementRead(id) => {
+            if let Some(announcement) = state
+                .data
+                .announcements
+                .items
+                .iter_mut()
+                .find(|announcement| announcement.id == id)
+            {
+                announcement.unread = !announcement.unread;
+            }
+            state.data.mark_dirty();
+
+            Command::perform(state.data.save(), Message::DataSaved)
+        }
+
         // Perform a specific action for a resource.
         Message::ResourceMessage((resource_type, module_id, path, message)) => match message {
             ResourceMessage::DownloadResource => {
@@ -320,19 +570,28 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
                 match api {
                     Some(api) => {
                         let modules_map = state.modules_map.clone();
+                        let folder_layout = state.settings.get_folder_layout().to_string();
                         let download_dir = state.settings.get_download_location().clone();
+                        let keep_previous_version = state.settings.get_keep_previous_versions();
                         let resources = get_resources_items(state, resource_type);
                         resources
                             .iter_mut()
                             .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
                             .map(|file| {
                                 file.download_status = FetchStatus::Fetching;
+                                file.unseen = false;
                                 match &file.resource {
                                     Some(resource) => {
                                         let resource = resource.clone();
                                         let path = file.path.clone();
-                                        let download_path =
-                                            file.local_resource_path(&modules_map, resource_type);
+                                        let download_path = file.local_resource_path(
+                                            &modules_map,
+                                            resource_type,
+                                            &folder_layout,
+                                        );
+                                        let replace = file.is_outdated();
+                                        let recorded_hash = file.download_hash.clone();
+                                        let recorded_version = file.download_time;
 
                                         Command::perform(
                                             async move {
@@ -341,7 +600,10 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
                                                     resource,
                                                     download_dir.clone(),
                                                     download_path,
-                                                    path.clone(),
+                                                    replace,
+                                                    recorded_hash,
+                                                    recorded_version,
+                                                    keep_previous_version,
                                                 )
                                                 .await;
                                                 (resource_type, module_id, path, result)
@@ -359,39 +621,401 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
                 }
             }
 
-            // Open downloaded file.
-            // TODO: doesn't work well on Linux.
+            // Open downloaded file, using the configured opener for its file type if any.
             ResourceMessage::OpenResource => {
-                Command::perform(async move { open::that(path) }, Message::OpenFileResult)
+                let (marked_seen, download_path) = match get_resources_items(state, resource_type)
+                    .iter_mut()
+                    .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
+                {
+                    Some(file) => {
+                        let marked_seen = file.unseen;
+                        file.unseen = false;
+                        (marked_seen, file.download_path.clone())
+                    }
+                    None => (false, None),
+                };
+
+                let mut commands = vec![];
+                if let Some(download_path) = download_path {
+                    commands.push(Command::perform(
+                        open_file(download_path, state.settings.get_opener_rules().clone()),
+                        Message::OpenFileResult,
+                    ));
+                }
+                // The data only needs to be saved if the resource is newly seen.
+                if marked_seen {
+                    state.data.mark_dirty();
+                    commands.push(Command::perform(state.data.save(), Message::DataSaved));
+                }
+
+                Command::batch(commands)
+            }
+
+            ResourceMessage::WatchStatusSelected(watch_status) => {
+                if let Some(file) = get_resources_items(state, resource_type)
+                    .iter_mut()
+                    .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
+                {
+                    file.watch_status = watch_status;
+                }
+                state.data.mark_dirty();
+                Command::perform(state.data.save(), Message::DataSaved)
+            }
+
+            // Move the local copy of a removed resource to the archive folder.
+            ResourceMessage::ArchiveResource => archive_resources(state, resource_type, |file| {
+                file.path.eq(&path) && file.module_id.eq(&module_id)
+            }),
+
+            ResourceMessage::ShowInFolder => {
+                let folder = get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
+                    .and_then(|file| file.download_path.as_ref())
+                    .and_then(|download_path| download_path.parent())
+                    .map(Path::to_path_buf);
+
+                match folder {
+                    Some(folder) => {
+                        Command::perform(async move { open_path(folder) }, Message::OpenFileResult)
+                    }
+                    None => Command::none(),
+                }
+            }
+
+            // Deleting the local copy has to be confirmed first, since it can't be undone.
+            ResourceMessage::DeleteLocalCopy => {
+                state
+                    .delete_confirmation
+                    .request(resource_type, vec![(module_id, path)]);
+                Command::none()
+            }
+
+            ResourceMessage::CopyPath => {
+                if let Some(download_path) = get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
+                    .and_then(|file| file.download_path.as_ref())
+                {
+                    clipboard.write(download_path.display().to_string());
+                }
+                Command::none()
+            }
+
+            ResourceMessage::CopyLink => {
+                if let Some(file) = get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
+                {
+                    clipboard.write(file.web_link(resource_type));
+                }
+                Command::none()
             }
         },
 
+        Message::ArchiveRemovedResources(resource_type) => {
+            archive_resources(state, resource_type, |_| true)
+        }
+
+        // Download every file in a folder which hasn't been downloaded or is outdated.
+        Message::DownloadFolder((resource_type, module_id, folder)) => {
+            download_resources(state, resource_type, |file| {
+                file.module_id == module_id
+                    && file.path.starts_with(&folder)
+                    && (file.download_path.is_none() || file.is_outdated())
+            })
+        }
+
+        // Open the local folder, which is found from the location of any file in it.
+        Message::OpenFolder((resource_type, module_id, folder)) => {
+            let download_dir = get_download_dir(state);
+            let folder_path = get_resources_items_ref(state, resource_type)
+                .iter()
+                .find(|file| file.module_id == module_id && file.path.starts_with(&folder))
+                .map(|file| {
+                    download_dir.join(file.local_folder_path(
+                        &state.modules_map,
+                        resource_type,
+                        state.settings.get_folder_layout(),
+                        &folder,
+                    ))
+                });
+
+            match folder_path {
+                Some(folder_path) => Command::perform(
+                    async move { open_path(folder_path) },
+                    Message::OpenFileResult,
+                ),
+                None => Command::none(),
+            }
+        }
+
+        // Selecting a resource while holding shift also selects every resource displayed
+        // between it and the previously selected resource.
+        Message::SelectResource((resource_type, module_id, path, selected)) => {
+            let range = if state.modifiers.shift {
+                get_resources_page_ref(state, resource_type).selection_range(
+                    get_resources_items_ref(state, resource_type),
+                    &state.modules_map,
+                    state.settings.get_resource_sort(resource_type),
+                    &module_id,
+                    &path,
+                )
+            } else {
+                vec![]
+            };
+
+            for file in get_resources_items(state, resource_type).iter_mut() {
+                if (file.module_id == module_id && file.path == path)
+                    || range.iter().any(|(range_module_id, range_path)| {
+                        file.module_id == *range_module_id && file.path == *range_path
+                    })
+                {
+                    file.selected = selected;
+                }
+            }
+            get_resources_page(state, resource_type).set_selection_anchor(module_id, path);
+
+            Command::none()
+        }
+
+        // Select every resource displayed on the page, or clear the selection.
+        Message::SelectAllResources((resource_type, selected)) => {
+            let displayed = get_resources_page_ref(state, resource_type)
+                .visible_files(
+                    get_resources_items_ref(state, resource_type),
+                    &state.modules_map,
+                    state.settings.get_resource_sort(resource_type),
+                )
+                .into_iter()
+                .map(|file| (file.module_id.clone(), file.path.clone()))
+                .collect::<HashSet<_>>();
+
+            for file in get_resources_items(state, resource_type).iter_mut() {
+                file.selected =
+                    selected && displayed.contains(&(file.module_id.clone(), file.path.clone()));
+            }
+
+            Command::none()
+        }
+
+        Message::BatchAction((resource_type, action)) => match action {
+            BatchAction::Download => download_resources(state, resource_type, |file| file.selected),
+            // Deleting local copies can't be undone, so it has to be confirmed first.
+            BatchAction::DeleteLocal => {
+                let resources = get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .filter(|file| file.selected && file.download_path.is_some())
+                    .map(|file| (file.module_id.clone(), file.path.clone()))
+                    .collect();
+                state.delete_confirmation.request(resource_type, resources);
+
+                Command::none()
+            }
+            BatchAction::MarkSeen => {
+                for file in get_resources_items(state, resource_type).iter_mut() {
+                    if file.selected {
+                        file.unseen = false;
+                    }
+                }
+                state.data.mark_dirty();
+
+                Command::perform(state.data.save(), Message::DataSaved)
+            }
+            BatchAction::OpenFolder => {
+                let mut folders = get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .filter(|file| file.selected)
+                    .filter_map(|file| file.download_path.as_ref())
+                    .filter_map(|download_path| download_path.parent())
+                    .map(Path::to_path_buf)
+                    .collect::<Vec<_>>();
+                folders.sort();
+                folders.dedup();
+
+                Command::batch(
+                    folders
+                        .into_iter()
+                        .map(|folder| {
+                            Command::perform(
+                                async move { open_path(folder) },
+                                Message::OpenFileResult,
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }
+        },
+
+        // Download every resource of a module which hasn't been downloaded or is outdated.
+        Message::SyncModule(module_id) => Command::batch(
+            [
+                ResourceType::File,
+                ResourceType::Multimedia,
+                ResourceType::Weblecture,
+                ResourceType::Conference,
+            ]
+            .iter()
+            .map(|resource_type| {
+                let resource_type = *resource_type;
+                let module_id = module_id.clone();
+                Command::perform(
+                    async move { (resource_type, module_id, PathBuf::new()) },
+                    Message::DownloadFolder,
+                )
+            })
+            .collect::<Vec<_>>(),
+        ),
+
+        Message::RetryFailedDownloads(()) => Command::batch(
+            [
+                ResourceType::File,
+                ResourceType::Multimedia,
+                ResourceType::Weblecture,
+                ResourceType::Conference,
+            ]
+            .iter()
+            .map(|resource_type| {
+                download_resources(state, *resource_type, |file| {
+                    matches!(file.download_status, FetchStatus::Error)
+                })
+            })
+            .collect::<Vec<_>>(),
+        ),
+
+        Message::OpenModuleFolder(module_id) => match state.modules_map.get(&module_id) {
+            Some(module) => {
+                let folder_path = get_download_dir(state).join(resolve_module_folder(
+                    state.settings.get_folder_layout(),
+                    module,
+                ));
+                Command::perform(
+                    async move { open_path(folder_path) },
+                    Message::OpenFileResult,
+                )
+            }
+            None => Command::none(),
+        },
+
+        // Only failures are reported, since opening a file shows it anyway.
         Message::OpenFileResult(result) => {
-            match result {
-                Ok(result) => println!("Opened file successfully: {}", result),
-                Err(err) => println!("Error opening file: {}", err),
+            if let Err(err) = result {
+                state
+                    .status_bar
+                    .set_error(format!("Error opening file: {}", err));
             }
             Command::none()
         }
 
         // Update resource download status, either marking as complete or error.
         Message::ResourceDownloaded((resource_type, module_id, path, message)) => {
+            let failed = message.is_err();
             let resources = get_resources_items(state, resource_type);
             resources
                 .iter_mut()
                 .find(|file| file.path.eq(&path) && file.module_id.eq(&module_id))
                 .map(|file| {
                     match message {
-                        Ok(path) => {
+                        Ok(downloaded) => {
                             // TODO: handle renames based on the new path returned.
+                            if let Some(previous_version_path) = downloaded.previous_version_path {
+                                let download_time = file.download_time;
+                                if let Some(version) = file
+                                    .history
+                                    .iter_mut()
+                                    .rev()
+                                    .find(|version| Some(version.last_updated) <= download_time)
+                                {
+                                    version.downloaded = true;
+                                    version.local_path = Some(previous_version_path);
+                                }
+                            }
                             file.download_status = FetchStatus::Idle;
-                            file.download_path = Some(path);
+                            file.download_path = Some(downloaded.path);
                             file.download_time = Some(file.last_updated);
+                            if let Some(info) = downloaded.info {
+                                file.download_hash = Some(info.hash);
+                                file.download_size = Some(info.size);
+                            }
+                            if let Some(local_copy_path) = downloaded.local_copy_path {
+                                file.local_copy_path = Some(local_copy_path);
+                            }
+                        }
+                        Err(_) => {
+                            file.download_status = FetchStatus::Error;
                         }
-                        // TODO: handle error
-                        Err(_) => {}
                     };
                 });
+            if failed {
+                state
+                    .status_bar
+                    .set_error(format!("Failed to download {}", path.display()));
+            }
+            state.data.mark_dirty();
+
+            Command::perform(state.data.save(), Message::DataSaved)
+        }
+
+        // Check the recorded download state of resources against the local file system.
+        Message::ReconcileResources(resource_type) => reconcile_resources(state, resource_type),
+
+        Message::ReconciledResources((resource_type, changed)) => {
+            apply_local_resources(state, resource_type, changed)
+        }
+
+        Message::DeleteLocalResources((resource_type, resources)) => {
+            let download_dir = get_download_dir(state);
+            let local_resources = collect_local_resources(
+                get_resources_items_ref(state, resource_type)
+                    .iter()
+                    .filter(|file| {
+                        file.download_path.is_some()
+                            && resources.iter().any(|(module_id, path)| {
+                                file.module_id.eq(module_id) && file.path.eq(path)
+                            })
+                    }),
+                &state.modules_map,
+                resource_type,
+                &download_dir,
+                state.settings.get_folder_layout(),
+            );
+
+            Command::perform(
+                async move { (resource_type, delete_local_resources(local_resources).await) },
+                Message::DeletedLocalResources,
+            )
+        }
+
+        Message::DeletedLocalResources((resource_type, (deleted, kept))) => {
+            if !kept.is_empty() {
+                state.status_bar.set_error(format!(
+                    "Kept {} {} which changed since being downloaded: {}",
+                    kept.len(),
+                    if kept.len() == 1 { "file" } else { "files" },
+                    kept.iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+
+            apply_local_resources(state, resource_type, deleted)
+        }
+
+        // Mark resources of a given type, or all modules and resources, as seen.
+        Message::MarkSeen(resource_type) => {
+            match resource_type {
+                Some(resource_type) => get_data_items(state, resource_type).mark_all_seen(),
+                None => {
+                    for module in state.data.modules.items.iter_mut() {
+                        module.unseen = false;
+                    }
+                    state.data.files.mark_all_seen();
+                    state.data.multimedia.mark_all_seen();
+                    state.data.weblectures.mark_all_seen();
+                    state.data.conferences.mark_all_seen();
+                }
+            }
             state.data.mark_dirty();
 
             Command::perform(state.data.save(), Message::DataSaved)
@@ -438,6 +1062,18 @@ pub fn handle_message(state: &mut FluminursDesktop, message: Message) -> Command
     }
 }
 
+fn get_data_items(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+) -> &mut DataItems<ResourceState> {
+    match resource_type {
+        ResourceType::File => &mut state.data.files,
+        ResourceType::Multimedia => &mut state.data.multimedia,
+        ResourceType::Weblecture => &mut state.data.weblectures,
+        ResourceType::Conference => &mut state.data.conferences,
+    }
+}
+
 fn get_resources_items<'a>(
     state: &'a mut FluminursDesktop,
     resource_type: ResourceType,
@@ -450,6 +1086,329 @@ fn get_resources_items<'a>(
     }
 }
 
+fn get_download_dir(state: &FluminursDesktop) -> PathBuf {
+    state
+        .settings
+        .get_download_location()
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn get_resources_items_ref(
+    state: &FluminursDesktop,
+    resource_type: ResourceType,
+) -> &Vec<ResourceState> {
+    match resource_type {
+        ResourceType::File => &state.data.files.items,
+        ResourceType::Multimedia => &state.data.multimedia.items,
+        ResourceType::Weblecture => &state.data.weblectures.items,
+        ResourceType::Conference => &state.data.conferences.items,
+    }
+}
+
+// Records the download state of resources found by checking the local file system.
+// Resources whose download state changed while the file system was being checked are
+// skipped, since the result no longer applies to them.
+fn apply_local_resources(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+    changed: Vec<LocalResource>,
+) -> Command<Message> {
+    if changed.is_empty() {
+        Command::none()
+    } else {
+        let resources = get_resources_items(state, resource_type);
+        for local_resource in changed {
+            if let Some(file) = resources.iter_mut().find(|file| {
+                file.path.eq(&local_resource.path)
+                    && file.module_id.eq(&local_resource.module_id)
+                    && local_resource.is_current(file)
+            }) {
+                file.download_path = local_resource.download_path;
+                file.download_time = local_resource.download_time;
+                file.download_hash = local_resource.download_hash;
+                file.download_size = local_resource.download_size;
+            }
+        }
+        state.data.mark_dirty();
+
+        Command::perform(state.data.save(), Message::DataSaved)
+    }
+}
+
+fn reconcile_resources(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+) -> Command<Message> {
+    let download_dir = get_download_dir(state);
+    let local_resources = collect_local_resources(
+        get_resources_items_ref(state, resource_type).iter(),
+        &state.modules_map,
+        resource_type,
+        &download_dir,
+        state.settings.get_folder_layout(),
+    );
+
+    Command::perform(
+        async move {
+            (
+                resource_type,
+                reconcile_local_resources(local_resources).await,
+            )
+        },
+        Message::ReconciledResources,
+    )
+}
+
+// Archived files are moved to an "Archive" folder in the download location, keeping the
+// same layout as the downloaded files.
+fn archive_resources<F>(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+    filter: F,
+) -> Command<Message>
+where
+    F: Fn(&ResourceState) -> bool,
+{
+    let archive_dir = get_download_dir(state).join("Archive");
+    let local_resources = collect_local_resources(
+        get_resources_items_ref(state, resource_type)
+            .iter()
+            .filter(|file| file.removed && file.download_path.is_some() && filter(*file)),
+        &state.modules_map,
+        resource_type,
+        &archive_dir,
+        state.settings.get_folder_layout(),
+    );
+
+    Command::perform(
+        async move {
+            (
+                resource_type,
+                archive_local_resources(local_resources).await,
+            )
+        },
+        Message::ReconciledResources,
+    )
+}
+
+// Keyboard shortcuts:
+// - Tab moves between the inputs on the login page.
+// - Ctrl+1 to Ctrl+9 switch between the pages in the header.
+// - Ctrl+R refreshes the current page.
+// - Up and down move between resources, Enter opens the resource and D downloads it.
+fn handle_key_press(
+    state: &mut FluminursDesktop,
+    key_code: keyboard::KeyCode,
+    modifiers: keyboard::Modifiers,
+) -> Command<Message> {
+    let resource_type = match state.current_page {
+        Page::Files => Some(ResourceType::File),
+        Page::Multimedia => Some(ResourceType::Multimedia),
+        Page::Weblectures => Some(ResourceType::Weblecture),
+        Page::Conferences => Some(ResourceType::Conference),
+        _ => None,
+    };
+
+    match state.current_page {
+        Page::Login => {
+            if key_code == keyboard::KeyCode::Tab {
+                state.pages.login.focus_next();
+            }
+            Command::none()
+        }
+        Page::Loading => Command::none(),
+        _ if modifiers.is_command_pressed() => {
+            let page = match key_code {
+                keyboard::KeyCode::Key1 => Some(Page::Dashboard),
+                keyboard::KeyCode::Key2 => Some(Page::Modules),
+                keyboard::KeyCode::Key3 => Some(Page::Announcements),
+                keyboard::KeyCode::Key4 => Some(Page::Files),
+                keyboard::KeyCode::Key5 => Some(Page::Multimedia),
+                keyboard::KeyCode::Key6 => Some(Page::Weblectures),
+                keyboard::KeyCode::Key7 => Some(Page::Conferences),
+                keyboard::KeyCode::Key8 => Some(Page::Search),
+                keyboard::KeyCode::Key9 => Some(Page::Settings),
+                _ => None,
+            };
+
+            match (page, key_code, &state.current_page) {
+                (Some(page), _, _) => Command::perform(async { page }, Message::SwitchPage),
+                (None, keyboard::KeyCode::R, Page::Dashboard) => {
+                    state.pages.dashboard.update(DashboardMessage::Refresh)
+                }
+                (None, keyboard::KeyCode::R, Page::Announcements) => {
+                    Command::perform(async {}, Message::LoadAnnouncements)
+                }
+                (None, keyboard::KeyCode::R, Page::Modules) => {
+                    Command::perform(async {}, Message::LoadModules)
+                }
+                (None, keyboard::KeyCode::R, Page::Module(module_id)) => {
+                    let module_id = module_id.clone();
+                    Command::perform(async { module_id }, Message::LoadModuleResources)
+                }
+                (None, keyboard::KeyCode::R, _) => match resource_type {
+                    Some(resource_type) => {
+                        Command::perform(async move { resource_type }, Message::LoadResources)
+                    }
+                    None => Command::none(),
+                },
+                _ => Command::none(),
+            }
+        }
+        _ => match resource_type {
+            Some(resource_type) => match key_code {
+                keyboard::KeyCode::Up | keyboard::KeyCode::Down => {
+                    let sort = state.settings.get_resource_sort(resource_type);
+                    let items = match resource_type {
+                        ResourceType::File => &state.data.files.items,
+                        ResourceType::Multimedia => &state.data.multimedia.items,
+                        ResourceType::Weblecture => &state.data.weblectures.items,
+                        ResourceType::Conference => &state.data.conferences.items,
+                    };
+                    let page = match resource_type {
+                        ResourceType::File => &mut state.pages.files,
+                        ResourceType::Multimedia => &mut state.pages.multimedia,
+                        ResourceType::Weblecture => &mut state.pages.weblectures,
+                        ResourceType::Conference => &mut state.pages.conferences,
+                    };
+                    page.move_cursor(
+                        items,
+                        &state.modules_map,
+                        sort,
+                        key_code == keyboard::KeyCode::Down,
+                    );
+
+                    Command::none()
+                }
+                keyboard::KeyCode::Enter | keyboard::KeyCode::D => {
+                    let message = if key_code == keyboard::KeyCode::Enter {
+                        ResourceMessage::OpenResource
+                    } else {
+                        ResourceMessage::DownloadResource
+                    };
+
+                    // Only the resource under the cursor which is displayed is acted on.
+                    let cursor = get_resources_page_ref(state, resource_type).displayed_cursor(
+                        get_resources_items_ref(state, resource_type),
+                        &state.modules_map,
+                        state.settings.get_resource_sort(resource_type),
+                    );
+                    match cursor {
+                        Some((module_id, path)) => Command::perform(
+                            async move { (resource_type, module_id, path, message) },
+                            Message::ResourceMessage,
+                        ),
+                        None => Command::none(),
+                    }
+                }
+                _ => Command::none(),
+            },
+            None => Command::none(),
+        },
+    }
+}
+
+// Downloads the matching resources which can be downloaded.
+fn download_resources<F>(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+    filter: F,
+) -> Command<Message>
+where
+    F: Fn(&ResourceState) -> bool,
+{
+    let commands = get_resources_items_ref(state, resource_type)
+        .iter()
+        .filter(|file| {
+            !file.removed && !matches!(file.download_status, FetchStatus::Fetching) && filter(*file)
+        })
+        .map(|file| {
+            let module_id = file.module_id.clone();
+            let path = file.path.clone();
+            Command::perform(
+                async move {
+                    (
+                        resource_type,
+                        module_id,
+                        path,
+                        ResourceMessage::DownloadResource,
+                    )
+                },
+                Message::ResourceMessage,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Command::batch(commands)
+}
+
+// Loads resources of the given type, either for all modules or for a single module.
+fn load_resources(
+    state: &mut FluminursDesktop,
+    resource_type: ResourceType,
+    module_id: Option<&str>,
+) -> Command<Message> {
+    match state.api.as_ref().cloned() {
+        Some(api) => {
+            if state.data.modules.items.len() > 0 {
+                let modules = state
+                    .data
+                    .modules
+                    .items
+                    .iter()
+                    .filter(|module| module_id.map_or(true, |module_id| module.id == module_id))
+                    .cloned()
+                    .filter_map(|module| module.internal_module)
+                    .collect();
+                let last_updated = SystemTime::now();
+                // Only a refresh of every module is shown as fetching the resource type.
+                if module_id.is_none() {
+                    let fetch_status = get_fetch_status(state, resource_type);
+                    *fetch_status = FetchStatus::Fetching;
+                }
+                let module_id = module_id.map(str::to_string);
+
+                Command::perform(
+                    async move {
+                        let result = match resource_type {
+                            ResourceType::File => {
+                                api::load_modules_files(api, modules, last_updated).await
+                            }
+                            ResourceType::Multimedia => {
+                                api::load_modules_multimedia(api, modules, last_updated).await
+                            }
+                            ResourceType::Weblecture => {
+                                api::load_modules_weblectures(api, modules, last_updated).await
+                            }
+                            ResourceType::Conference => {
+                                api::load_modules_conferences(api, modules, last_updated).await
+                            }
+                        };
+
+                        (resource_type, module_id, result)
+                    },
+                    Message::LoadedResources,
+                )
+            } else {
+                // No modules
+                Command::none()
+            }
+        }
+        // TODO: refresh API?
+        None => Command::none(),
+    }
+}
+
+fn get_resources_page_ref(state: &FluminursDesktop, resource_type: ResourceType) -> &ResourcesPage {
+    match resource_type {
+        ResourceType::File => &state.pages.files,
+        ResourceType::Multimedia => &state.pages.multimedia,
+        ResourceType::Weblecture => &state.pages.weblectures,
+        ResourceType::Conference => &state.pages.conferences,
+    }
+}
+
 fn get_resources_page(
     state: &mut FluminursDesktop,
     resource_type: ResourceType,
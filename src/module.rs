@@ -2,10 +2,13 @@ use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
-use iced::{Element, Length, Row, Text};
+use iced::{button, Align, Button, Element, Length, Row, Text};
 
 use fluminurs::module::Module as FluminursModule;
 
+use crate::resource::ResourceType;
+use crate::theme::{self, Theme};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Module {
     pub id: String,
@@ -15,14 +18,21 @@ pub struct Module {
     pub is_taking: bool,
     pub is_teaching: bool,
     pub last_updated: SystemTime,
+    pub first_seen: Option<SystemTime>,
+    #[serde(default)]
+    pub unseen: bool,
 
     #[serde(skip)]
     pub internal_module: Option<FluminursModule>,
+    #[serde(skip)]
+    open_button: button::State,
 }
 
 #[derive(Debug, Clone)]
 pub enum ModuleMessage {
     RefreshModules,
+    MarkAllSeen,
+    OpenModule(String),
 }
 
 impl Module {
@@ -35,7 +45,10 @@ impl Module {
             is_taking: false,
             is_teaching: false,
             last_updated: SystemTime::UNIX_EPOCH,
+            first_seen: None,
+            unseen: false,
             internal_module: None,
+            open_button: button::State::new(),
         }
     }
 
@@ -48,15 +61,56 @@ impl Module {
             is_taking: module.is_taking(),
             is_teaching: module.is_teaching(),
             last_updated,
+            first_seen: Some(SystemTime::now()),
+            unseen: true,
             internal_module: Some(module),
+            open_button: button::State::new(),
         }
     }
 
-    pub fn view(&self) -> Element<ModuleMessage> {
-        Row::new()
+    pub fn view(
+        &mut self,
+        unseen_resources: usize,
+        watched_counts: &[(ResourceType, usize, usize)],
+        theme: Theme,
+    ) -> Element<ModuleMessage> {
+        let content = Row::new()
             .height(Length::Units(30))
+            .align_items(Align::Center)
             .spacing(20)
-            .push(Text::new(format!("{} {}", self.code, self.name)))
-            .into()
+            .push(
+                Button::new(
+                    &mut self.open_button,
+                    Text::new(format!("{} {}", self.code, self.name)),
+                )
+                .style(theme::Button(theme))
+                .on_press(ModuleMessage::OpenModule(self.id.clone())),
+            );
+
+        let content = if self.unseen {
+            content.push(Text::new("New"))
+        } else {
+            content
+        };
+
+        let content = if unseen_resources > 0 {
+            content.push(Text::new(format!("{} unseen", unseen_resources)))
+        } else {
+            content
+        };
+
+        let content =
+            watched_counts
+                .iter()
+                .fold(content, |content, (resource_type, watched, total)| {
+                    content.push(Text::new(format!(
+                        "{} of {} {} watched",
+                        watched,
+                        total,
+                        resource_type.name().to_lowercase()
+                    )))
+                });
+
+        content.into()
     }
 }
@@ -0,0 +1,190 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// A command used to open files with any of the given extensions, such as `mpv` for
+// videos or `zathura` for PDFs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenerRule {
+    // Comma-separated list of extensions, without the leading dot, e.g. "mp4, mkv".
+    pub extensions: String,
+    // The path of the file replaces `{}` if present, and is appended otherwise. Arguments
+    // containing spaces can be quoted, as in a shell.
+    pub command: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum OpenerRuleEdit {
+    Add,
+    Remove(usize),
+    ExtensionsChanged(usize, String),
+    CommandChanged(usize, String),
+}
+
+impl OpenerRule {
+    fn matches(&self, path: &Path) -> bool {
+        let extension = match path.extension() {
+            Some(extension) => extension.to_string_lossy().to_lowercase(),
+            None => return false,
+        };
+
+        self.extensions
+            .split(',')
+            .map(|rule_extension| rule_extension.trim().trim_start_matches('.'))
+            .any(|rule_extension| rule_extension.to_lowercase() == extension)
+    }
+}
+
+// Open a file using the first matching rule, falling back to the system default. The
+// command isn't waited on, since players and viewers keep running until they are closed.
+pub async fn open_file(path: PathBuf, rules: Vec<OpenerRule>) -> io::Result<()> {
+    let rule = rules
+        .into_iter()
+        .find(|rule| !rule.command.trim().is_empty() && rule.matches(&path));
+
+    match rule {
+        Some(rule) => run_command(&rule.command, &path.to_string_lossy()),
+        None => open_path(path),
+    }
+}
+
+// Starts a command with the given target, which is either a path or a URL, without
+// waiting for it to exit.
+pub fn run_command(command: &str, target: &str) -> io::Result<()> {
+    let (program, args) = build_command(command, target)?;
+
+    tokio::process::Command::new(program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+}
+
+// Splits a command into the program and its arguments, with the target replacing `{}`
+// if present and appended otherwise.
+fn build_command(command: &str, target: &str) -> io::Result<(String, Vec<String>)> {
+    let mut parts = split_command(command)?.into_iter();
+    let program = parts
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the command is empty"))?;
+    let mut args = parts.collect::<Vec<_>>();
+
+    if args.iter().any(|arg| arg.contains("{}")) {
+        args = args.iter().map(|arg| arg.replace("{}", target)).collect();
+    } else {
+        args.push(target.to_string());
+    }
+
+    Ok((program, args))
+}
+
+// Open a file or folder using the system default.
+pub fn open_path<P: AsRef<std::ffi::OsStr>>(path: P) -> io::Result<()> {
+    let status = open::that(path)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::Other, status.to_string()))
+    }
+}
+
+// Splits a command into the program and its arguments. Single and double quotes group
+// words containing spaces. Outside of single quotes, a backslash escapes a following
+// quote, backslash or space, and is kept otherwise so that Windows paths still work.
+fn split_command(command: &str) -> io::Result<Vec<String>> {
+    let mut parts = vec![];
+    let mut part: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => part.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let escaped = match chars.peek() {
+                    Some(&next) if next == '\\' || next == '\'' || next == '"' => chars.next(),
+                    Some(&next) if next.is_whitespace() => chars.next(),
+                    _ => None,
+                };
+                part.get_or_insert_with(String::new)
+                    .push(escaped.unwrap_or(c));
+            }
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                part.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => parts.extend(part.take()),
+            (_, c) => part.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unmatched quote in command: {}", command),
+        ));
+    }
+    parts.extend(part);
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn split_command_groups_quoted_arguments() {
+        assert_eq!(
+            split_command(r#""C:\Program Files\VLC\vlc.exe" --title 'My video' "a \"b\"""#)
+                .unwrap(),
+            strings(&[
+                r"C:\Program Files\VLC\vlc.exe",
+                "--title",
+                "My video",
+                r#"a "b""#
+            ])
+        );
+        assert_eq!(
+            split_command(r"my\ player ''").unwrap(),
+            strings(&["my player", ""])
+        );
+    }
+
+    #[test]
+    fn split_command_keeps_trailing_backslash() {
+        assert_eq!(
+            split_command(r"player C:\Videos\").unwrap(),
+            strings(&["player", r"C:\Videos\"])
+        );
+    }
+
+    #[test]
+    fn split_command_rejects_unmatched_quote() {
+        assert_eq!(
+            split_command(r#"player "unfinished"#).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn build_command_substitutes_target() {
+        assert_eq!(
+            build_command("mpv --fs {} --loop", "/tmp/a b.mp4").unwrap(),
+            (
+                "mpv".to_string(),
+                strings(&["--fs", "/tmp/a b.mp4", "--loop"])
+            )
+        );
+        assert_eq!(
+            build_command("zathura", "/tmp/a.pdf").unwrap(),
+            ("zathura".to_string(), strings(&["/tmp/a.pdf"]))
+        );
+        assert!(build_command("  ", "/tmp/a.pdf").is_err());
+    }
+}
@@ -1,13 +1,21 @@
+pub mod announcements;
+pub mod dashboard;
 pub mod loading;
 pub mod login;
+pub mod module;
 pub mod modules;
 pub mod resources;
+pub mod search;
 pub mod settings;
 
+use crate::pages::announcements::AnnouncementsPage;
+use crate::pages::dashboard::DashboardPage;
 use crate::pages::loading::LoadingPage;
 use crate::pages::login::LoginPage;
+use crate::pages::module::ModulePage;
 use crate::pages::modules::ModulesPage;
 use crate::pages::resources::ResourcesPage;
+use crate::pages::search::SearchPage;
 use crate::pages::settings::SettingsPage;
 use crate::resource::ResourceType;
 
@@ -16,22 +24,30 @@ pub enum Page {
     Loading,
     Login,
     Settings,
+    Dashboard,
     Modules,
+    Module(String),
+    Announcements,
     Files,
     Multimedia,
     Weblectures,
     Conferences,
+    Search,
 }
 
 pub struct Pages {
     pub loading: LoadingPage,
     pub login: LoginPage,
     pub settings: SettingsPage,
+    pub dashboard: DashboardPage,
     pub modules: ModulesPage,
+    pub module: ModulePage,
+    pub announcements: AnnouncementsPage,
     pub files: ResourcesPage,
     pub multimedia: ResourcesPage,
     pub weblectures: ResourcesPage,
     pub conferences: ResourcesPage,
+    pub search: SearchPage,
 }
 
 impl Pages {
@@ -40,11 +56,15 @@ impl Pages {
             loading: LoadingPage::default(),
             login: LoginPage::default(),
             settings: SettingsPage::default(),
+            dashboard: DashboardPage::default(),
             modules: ModulesPage::default(),
+            module: ModulePage::default(),
+            announcements: AnnouncementsPage::default(),
             files: ResourcesPage::default(ResourceType::File),
             multimedia: ResourcesPage::default(ResourceType::Multimedia),
             weblectures: ResourcesPage::default(ResourceType::Weblecture),
             conferences: ResourcesPage::default(ResourceType::Conference),
+            search: SearchPage::default(),
         }
     }
 }
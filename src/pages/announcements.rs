@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use iced::{
+    button, scrollable, Button, Column, Command, Container, Element, Length, Row, Scrollable, Text,
+};
+
+use crate::announcement::{
+    announcement_buttons, Announcement, AnnouncementButtons, AnnouncementMessage,
+};
+use crate::data::{DataItems, FetchStatus};
+use crate::message::Message;
+use crate::module::Module;
+use crate::theme::{self, Theme};
+
+#[derive(Debug, Clone)]
+pub struct AnnouncementsPage {
+    refresh_button: button::State,
+    mark_read_button: button::State,
+    rows: Vec<AnnouncementButtons>,
+    scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum AnnouncementsMessage {
+    Refresh,
+    MarkAllRead,
+    AnnouncementMessage(String, AnnouncementMessage),
+}
+
+impl AnnouncementsPage {
+    pub fn default() -> Self {
+        AnnouncementsPage {
+            refresh_button: button::State::new(),
+            mark_read_button: button::State::new(),
+            rows: vec![],
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: AnnouncementsMessage) -> Command<Message> {
+        match message {
+            AnnouncementsMessage::Refresh => Command::perform(async {}, Message::LoadAnnouncements),
+            AnnouncementsMessage::MarkAllRead => {
+                Command::perform(async {}, Message::MarkAnnouncementsRead)
+            }
+            AnnouncementsMessage::AnnouncementMessage(id, AnnouncementMessage::ToggleRead) => {
+                Command::perform(async { id }, Message::ToggleAnnouncementRead)
+            }
+        }
+    }
+
+    pub fn view<'a>(
+        &'a mut self,
+        announcements: &DataItems<Announcement>,
+        modules_map: &HashMap<String, Module>,
+        theme: Theme,
+    ) -> Element<'a, AnnouncementsMessage> {
+        let refresh_button: Button<_> = match announcements.fetch_status {
+            FetchStatus::Fetching => Button::new(&mut self.refresh_button, Text::new("Loading…")),
+            _ => Button::new(&mut self.refresh_button, Text::new("Refresh"))
+                .on_press(AnnouncementsMessage::Refresh),
+        }
+        .style(theme::Button(theme));
+
+        let mark_read_button =
+            Button::new(&mut self.mark_read_button, Text::new("Mark all as read"))
+                .style(theme::Button(theme))
+                .on_press(AnnouncementsMessage::MarkAllRead);
+
+        let rows = announcement_buttons(&mut self.rows, announcements.items.len());
+        let list: Element<_> = if announcements.items.is_empty() {
+            Text::new("No announcements found").into()
+        } else {
+            announcements
+                .items
+                .iter()
+                .zip(rows.iter_mut())
+                .fold(
+                    Column::new().spacing(30),
+                    |column, (announcement, buttons)| {
+                        let id = announcement.id.clone();
+                        column.push(
+                            announcement
+                                .view(buttons, modules_map.get(&announcement.module_id), theme)
+                                .map(move |message| {
+                                    AnnouncementsMessage::AnnouncementMessage(id.clone(), message)
+                                }),
+                        )
+                    },
+                )
+                .into()
+        };
+
+        let content = Column::new()
+            .spacing(20)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(refresh_button)
+                    .push(mark_read_button),
+            )
+            .push(list);
+
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
+
+        Container::new(scrollable).height(Length::Fill).into()
+    }
+}
@@ -0,0 +1,256 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use iced::{
+    button, scrollable, Button, Column, Command, Container, Element, Length, Row, Scrollable, Text,
+};
+
+use crate::data::{Data, DataItems, FetchStatus};
+use crate::message::Message;
+use crate::module::Module;
+use crate::resource::{
+    resource_buttons, ResourceButtons, ResourceMessage, ResourceState, ResourceType,
+};
+use crate::theme::{self, Theme};
+use crate::utils::format_time;
+
+// Number of resources listed in each section of the dashboard.
+const RECENT_COUNT: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct DashboardPage {
+    refresh_button: button::State,
+    retry_button: button::State,
+    rows: Vec<ResourceButtons>,
+    scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum DashboardMessage {
+    Refresh,
+    RetryFailed,
+    ResourceMessage(ResourceType, String, PathBuf, ResourceMessage),
+}
+
+impl DashboardPage {
+    pub fn default() -> Self {
+        DashboardPage {
+            refresh_button: button::State::new(),
+            retry_button: button::State::new(),
+            rows: vec![],
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: DashboardMessage) -> Command<Message> {
+        match message {
+            DashboardMessage::Refresh => Command::batch(vec![
+                Command::perform(async {}, Message::LoadModules),
+                Command::perform(async { ResourceType::File }, Message::LoadResources),
+                Command::perform(async { ResourceType::Multimedia }, Message::LoadResources),
+                Command::perform(async { ResourceType::Weblecture }, Message::LoadResources),
+                Command::perform(async { ResourceType::Conference }, Message::LoadResources),
+                Command::perform(async {}, Message::LoadAnnouncements),
+            ]),
+            DashboardMessage::RetryFailed => {
+                Command::perform(async {}, Message::RetryFailedDownloads)
+            }
+            DashboardMessage::ResourceMessage(resource_type, module_id, path, message) => {
+                Command::perform(
+                    async move { (resource_type, module_id, path, message) },
+                    Message::ResourceMessage,
+                )
+            }
+        }
+    }
+
+    pub fn view<'a>(
+        &'a mut self,
+        data: &Data,
+        modules_map: &'a HashMap<String, Module>,
+        theme: Theme,
+    ) -> Element<'a, DashboardMessage> {
+        let fetching = [
+            &data.files,
+            &data.multimedia,
+            &data.weblectures,
+            &data.conferences,
+        ]
+        .iter()
+        .any(|items| matches!(items.fetch_status, FetchStatus::Fetching))
+            || matches!(data.modules.fetch_status, FetchStatus::Fetching)
+            || matches!(data.announcements.fetch_status, FetchStatus::Fetching);
+
+        let sync_status = Column::new()
+            .spacing(10)
+            .push(Text::new("Sync status").size(24))
+            .push(view_fetch_status("Modules", &data.modules))
+            .push(view_fetch_status(ResourceType::File.name(), &data.files))
+            .push(view_fetch_status(
+                ResourceType::Multimedia.name(),
+                &data.multimedia,
+            ))
+            .push(view_fetch_status(
+                ResourceType::Weblecture.name(),
+                &data.weblectures,
+            ))
+            .push(view_fetch_status(
+                ResourceType::Conference.name(),
+                &data.conferences,
+            ))
+            .push(view_fetch_status("Announcements", &data.announcements));
+
+        let mut downloaded = all_resources(data)
+            .filter_map(|(resource_type, resource)| {
+                resource.download_time.map(|download_time| {
+                    (
+                        download_time,
+                        resource
+                            .display_path(modules_map, resource_type)
+                            .display()
+                            .to_string(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        downloaded.sort_by_key(|(download_time, _)| Reverse(*download_time));
+        let recent_downloads = downloaded.into_iter().take(RECENT_COUNT).fold(
+            Column::new()
+                .spacing(10)
+                .push(Text::new("Recently downloaded").size(24)),
+            |column, (download_time, label)| {
+                column.push(Text::new(format!(
+                    "{} ({})",
+                    label,
+                    format_time(download_time)
+                )))
+            },
+        );
+
+        let failed = all_resources(data)
+            .filter(|(_, resource)| matches!(resource.download_status, FetchStatus::Error))
+            .map(|(resource_type, resource)| {
+                resource
+                    .display_path(modules_map, resource_type)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        let retry_button =
+            Button::new(&mut self.retry_button, Text::new("Retry all")).style(theme::Button(theme));
+        let retry_button = if failed.is_empty() {
+            retry_button
+        } else {
+            retry_button.on_press(DashboardMessage::RetryFailed)
+        };
+        let failed_downloads = failed.into_iter().fold(
+            Column::new().spacing(10).push(
+                Row::new()
+                    .spacing(20)
+                    .push(Text::new("Failed downloads").size(24))
+                    .push(retry_button),
+            ),
+            |column, label| column.push(Text::new(label)),
+        );
+
+        let mut updated = all_resources(data)
+            .filter(|(_, resource)| !resource.removed)
+            .collect::<Vec<_>>();
+        updated.sort_by_key(|(_, resource)| Reverse(resource.last_updated));
+        updated.truncate(RECENT_COUNT);
+        let rows = resource_buttons(&mut self.rows, updated.len());
+        let recent_updates =
+            updated.into_iter().zip(rows.iter_mut()).fold(
+                Column::new()
+                    .spacing(10)
+                    .push(Text::new("Recently updated").size(24)),
+                |column, ((resource_type, resource), buttons)| {
+                    let module_id = resource.module_id.clone();
+                    let path = resource.path.clone();
+                    let label = format!(
+                        "{} ({})",
+                        resource.display_path(modules_map, resource_type).display(),
+                        format_time(resource.last_updated)
+                    );
+                    column.push(resource.view_with_label(buttons, label, theme).map(
+                        move |message| {
+                            DashboardMessage::ResourceMessage(
+                                resource_type,
+                                module_id.clone(),
+                                path.clone(),
+                                message,
+                            )
+                        },
+                    ))
+                },
+            );
+
+        let refresh_button: Button<_> = if fetching {
+            Button::new(&mut self.refresh_button, Text::new("Loading…"))
+        } else {
+            Button::new(&mut self.refresh_button, Text::new("Refresh all"))
+                .on_press(DashboardMessage::Refresh)
+        }
+        .style(theme::Button(theme));
+
+        let content = Column::new()
+            .spacing(20)
+            .push(refresh_button)
+            .push(sync_status)
+            .push(recent_updates)
+            .push(recent_downloads)
+            .push(failed_downloads);
+
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
+
+        Container::new(scrollable).height(Length::Fill).into()
+    }
+}
+
+fn view_fetch_status<'a, T>(name: &str, items: &DataItems<T>) -> Element<'a, DashboardMessage> {
+    let status = match items.fetch_status {
+        FetchStatus::Idle => "Idle",
+        FetchStatus::Fetching => "Loading…",
+        FetchStatus::Error => "Error",
+    };
+    let last_updated = if items.last_updated == SystemTime::UNIX_EPOCH {
+        "never".to_string()
+    } else {
+        format_time(items.last_updated)
+    };
+
+    Text::new(format!(
+        "{}: {}, last updated {}",
+        name, status, last_updated
+    ))
+    .into()
+}
+
+fn all_resources(data: &Data) -> impl Iterator<Item = (ResourceType, &ResourceState)> {
+    data.files
+        .items
+        .iter()
+        .map(|resource| (ResourceType::File, resource))
+        .chain(
+            data.multimedia
+                .items
+                .iter()
+                .map(|resource| (ResourceType::Multimedia, resource)),
+        )
+        .chain(
+            data.weblectures
+                .items
+                .iter()
+                .map(|resource| (ResourceType::Weblecture, resource)),
+        )
+        .chain(
+            data.conferences
+                .items
+                .iter()
+                .map(|resource| (ResourceType::Conference, resource)),
+        )
+}
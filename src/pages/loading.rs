@@ -1,29 +1,160 @@
-use iced::{Command, Container, Element, Length, Text};
+use iced::{
+    button, Align, Button, Column, Command, Container, Element, HorizontalAlignment, Length, Row,
+    Text,
+};
 
 use crate::message::Message;
+use crate::pages::Page;
+use crate::theme::{self, Theme};
 
 #[derive(Debug, Clone)]
-pub struct LoadingPage {}
+pub struct LoadingPage {
+    settings: LoadingState,
+    data: LoadingState,
+    login: LoadingState,
+    retry_button: button::State,
+    offline_button: button::State,
+    login_button: button::State,
+}
 
 #[derive(Debug, Clone)]
-pub enum LoadingMessage {}
+pub enum LoadingMessage {
+    StepChanged(LoadingStep, LoadingState),
+    Retry,
+    ContinueOffline,
+    SignIn,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum LoadingStep {
+    Settings,
+    Data,
+    Login,
+}
 
 #[derive(Debug, Clone)]
-pub enum LoadingState {}
+pub enum LoadingState {
+    Pending,
+    InProgress,
+    Done,
+    Skipped,
+    Failed(String),
+}
+
+impl LoadingStep {
+    fn title(&self) -> &'static str {
+        match self {
+            LoadingStep::Settings => "Loading settings",
+            LoadingStep::Data => "Loading saved data",
+            LoadingStep::Login => "Signing in",
+        }
+    }
+}
 
 impl LoadingPage {
     pub fn default() -> Self {
-        LoadingPage {}
+        LoadingPage {
+            settings: LoadingState::InProgress,
+            data: LoadingState::InProgress,
+            login: LoadingState::Pending,
+            retry_button: button::State::new(),
+            offline_button: button::State::new(),
+            login_button: button::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: LoadingMessage) -> Command<Message> {
+        match message {
+            LoadingMessage::StepChanged(step, loading_state) => {
+                match step {
+                    LoadingStep::Settings => self.settings = loading_state,
+                    LoadingStep::Data => self.data = loading_state,
+                    LoadingStep::Login => self.login = loading_state,
+                }
+                Command::none()
+            }
+            LoadingMessage::Retry => Command::perform(async {}, Message::RetryStartup),
+            LoadingMessage::ContinueOffline => {
+                Command::perform(async { Page::Dashboard }, Message::SwitchPage)
+            }
+            LoadingMessage::SignIn => Command::perform(async { Page::Login }, Message::SwitchPage),
+        }
     }
 
-    pub fn update(&mut self, _message: LoadingMessage) -> Command<Message> {
-        Command::none()
+    pub fn is_signing_in(&self) -> bool {
+        matches!(self.login, LoadingState::InProgress)
     }
 
-    pub fn view(&mut self) -> Element<LoadingMessage> {
-        let loading_message = Text::new("Loading…");
+    pub fn view(&mut self, theme: Theme) -> Element<LoadingMessage> {
+        let steps = [
+            (LoadingStep::Settings, &self.settings),
+            (LoadingStep::Data, &self.data),
+            (LoadingStep::Login, &self.login),
+        ];
+        let failed = steps
+            .iter()
+            .any(|(_, loading_state)| matches!(loading_state, LoadingState::Failed(_)));
+        let signing_in = matches!(self.login, LoadingState::InProgress);
+
+        let content = steps.iter().fold(
+            Column::new()
+                .align_items(Align::Center)
+                .max_width(400)
+                .spacing(10)
+                .push(
+                    Text::new("fluminurs-desktop")
+                        .size(40)
+                        .width(Length::Fill)
+                        .horizontal_alignment(HorizontalAlignment::Center),
+                ),
+            |column, (step, loading_state)| {
+                let status = match loading_state {
+                    LoadingState::Pending => Text::new("Waiting"),
+                    LoadingState::InProgress => Text::new("In progress…"),
+                    LoadingState::Done => Text::new("Done"),
+                    LoadingState::Skipped => Text::new("Skipped"),
+                    LoadingState::Failed(reason) => {
+                        Text::new(reason.clone()).color(theme.error_color())
+                    }
+                };
+
+                column.push(
+                    Row::new()
+                        .spacing(20)
+                        .push(Text::new(step.title()).width(Length::Units(160)))
+                        .push(status.width(Length::Fill)),
+                )
+            },
+        );
+
+        // Saved data can still be browsed while signing in or after a step has failed.
+        let buttons = Row::new().spacing(20);
+        let buttons = if failed {
+            buttons
+                .push(
+                    Button::new(&mut self.retry_button, Text::new("Retry"))
+                        .style(theme::Button(theme))
+                        .on_press(LoadingMessage::Retry),
+                )
+                .push(
+                    Button::new(&mut self.login_button, Text::new("Sign in again"))
+                        .style(theme::Button(theme))
+                        .on_press(LoadingMessage::SignIn),
+                )
+        } else {
+            buttons
+        };
+        let buttons = if failed || signing_in {
+            buttons.push(
+                Button::new(&mut self.offline_button, Text::new("Continue offline"))
+                    .style(theme::Button(theme))
+                    .on_press(LoadingMessage::ContinueOffline),
+            )
+        } else {
+            buttons
+        };
 
-        Container::new(loading_message)
+        Container::new(content.push(buttons))
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x()
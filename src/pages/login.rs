@@ -1,11 +1,12 @@
 use iced::{
-    button, text_input, Align, Button, Color, Column, Command, Container, Element,
-    HorizontalAlignment, Length, Text, TextInput,
+    button, text_input, Align, Button, Checkbox, Column, Command, Container, Element,
+    HorizontalAlignment, Length, Row, Text, TextInput,
 };
 
-use crate::api;
+use crate::api::{self, LoginError};
 use crate::message::Message;
 use crate::pages::Page;
+use crate::theme::{self, Theme};
 use crate::utils::clean_username;
 
 #[derive(Debug, Clone)]
@@ -16,15 +17,20 @@ pub struct LoginPage {
     password_input: text_input::State,
     login_button: button::State,
     cancel_button: button::State,
+    show_password: bool,
     login_state: LoginState,
+    previous_page: Option<Page>,
 }
 
 #[derive(Debug, Clone)]
 pub enum LoginMessage {
     UsernameEdited(String),
     PasswordEdited(String),
+    ShowPasswordToggled(bool),
+    RememberMeToggled(bool),
     Submit,
-    Failed,
+    Succeeded,
+    Failed(LoginError),
     Cancel,
 }
 
@@ -32,7 +38,7 @@ pub enum LoginMessage {
 pub enum LoginState {
     Initial,
     SigningIn,
-    Error,
+    Error(LoginError),
 }
 
 impl LoginPage {
@@ -44,7 +50,9 @@ impl LoginPage {
             password_input: text_input::State::new(),
             login_button: button::State::new(),
             cancel_button: button::State::new(),
+            show_password: false,
             login_state: LoginState::Initial,
+            previous_page: None,
         }
     }
 
@@ -58,6 +66,14 @@ impl LoginPage {
                 self.password = password;
                 Command::none()
             }
+            LoginMessage::ShowPasswordToggled(show_password) => {
+                self.show_password = show_password;
+                Command::none()
+            }
+            // Remembering the password allows signing in automatically on startup.
+            LoginMessage::RememberMeToggled(remember_me) => {
+                Command::perform(async move { remember_me }, Message::ToggleSavePassword)
+            }
             LoginMessage::Submit => {
                 self.login_state = LoginState::SigningIn;
                 Command::perform(
@@ -65,17 +81,53 @@ impl LoginPage {
                     Message::LoadedAPI,
                 )
             }
-            LoginMessage::Failed => {
-                self.login_state = LoginState::Error;
+            LoginMessage::Succeeded => {
+                self.login_state = LoginState::Initial;
+                Command::none()
+            }
+            LoginMessage::Failed(error) => {
+                self.login_state = LoginState::Error(error);
                 Command::none()
             }
-            // Go back to settings page if login is cancelled
-            // TODO: store previous page?
-            LoginMessage::Cancel => Command::perform(async { Page::Settings }, Message::SwitchPage),
+            // Go back to the page which opened the login page if login is cancelled.
+            LoginMessage::Cancel => {
+                let page = self.take_previous_page().unwrap_or(Page::Settings);
+                Command::perform(async { page }, Message::SwitchPage)
+            }
         }
     }
 
-    pub fn view(&mut self, cancelable: bool) -> Element<LoginMessage> {
+    pub fn is_signing_in(&self) -> bool {
+        matches!(self.login_state, LoginState::SigningIn)
+    }
+
+    pub fn set_previous_page(&mut self, page: Page) {
+        self.previous_page = Some(page);
+    }
+
+    pub fn take_previous_page(&mut self) -> Option<Page> {
+        self.previous_page.take()
+    }
+
+    // Moves focus to the next input, wrapping around to the first input.
+    pub fn focus_next(&mut self) {
+        if self.username_input.is_focused() {
+            self.username_input.unfocus();
+            self.password_input.focus();
+            self.password_input.move_cursor_to_end();
+        } else {
+            self.password_input.unfocus();
+            self.username_input.focus();
+            self.username_input.move_cursor_to_end();
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        cancelable: bool,
+        remember_me: bool,
+        theme: Theme,
+    ) -> Element<LoginMessage> {
         let LoginPage {
             username,
             password,
@@ -83,10 +135,11 @@ impl LoginPage {
             password_input,
             login_button,
             cancel_button,
+            show_password,
             login_state,
+            ..
         } = self;
 
-        // TODO: tab navigation, error handling
         let username_input = TextInput::new(
             username_input,
             "Username",
@@ -102,20 +155,40 @@ impl LoginPage {
             &password,
             LoginMessage::PasswordEdited,
         )
-        .password()
         .on_submit(LoginMessage::Submit)
         .padding(10);
+        let password_input = if *show_password {
+            password_input
+        } else {
+            password_input.password()
+        };
 
-        let error_message = match *login_state {
-            LoginState::Error => "Username or password is incorrect",
-            _ => "",
+        let options = Row::new()
+            .spacing(20)
+            .push(
+                Checkbox::new(
+                    *show_password,
+                    "Show password",
+                    LoginMessage::ShowPasswordToggled,
+                )
+                .style(theme::Checkbox(theme)),
+            )
+            .push(
+                Checkbox::new(remember_me, "Remember me", LoginMessage::RememberMeToggled)
+                    .style(theme::Checkbox(theme)),
+            );
+
+        let error_message = match login_state {
+            LoginState::Error(error) => error.to_string(),
+            _ => "".to_string(),
         };
 
         let button_text = match *login_state {
             LoginState::SigningIn => "Signing in…",
             _ => "Sign in",
         };
-        let login_button = Button::new(login_button, Text::new(button_text));
+        let login_button =
+            Button::new(login_button, Text::new(button_text)).style(theme::Button(theme));
 
         // Disable login button if signing in is in progress
         let login_button = match *login_state {
@@ -133,19 +206,17 @@ impl LoginPage {
                     .width(Length::Fill)
                     .horizontal_alignment(HorizontalAlignment::Center),
             )
-            .push(Text::new(error_message).color(Color {
-                r: 1.0,
-                g: 0.0,
-                b: 0.0,
-                a: 1.0,
-            }))
-            .push(username_input.style(style::TextInput::UsernameInput))
-            .push(password_input.style(style::TextInput::UsernameInput))
+            .push(Text::new(error_message).color(theme.error_color()))
+            .push(username_input.style(theme::TextInput(theme)))
+            .push(password_input.style(theme::TextInput(theme)))
+            .push(options)
             .push(login_button);
 
         let content = if cancelable {
             content.push(
-                Button::new(cancel_button, Text::new("Cancel")).on_press(LoginMessage::Cancel),
+                Button::new(cancel_button, Text::new("Cancel"))
+                    .style(theme::Button(theme))
+                    .on_press(LoginMessage::Cancel),
             )
         } else {
             content
@@ -159,54 +230,3 @@ impl LoginPage {
             .into()
     }
 }
-
-mod style {
-    use iced::{text_input, Background, Color};
-
-    pub enum TextInput {
-        UsernameInput,
-    }
-
-    impl text_input::StyleSheet for TextInput {
-        fn active(&self) -> text_input::Style {
-            text_input::Style {
-                background: Background::Color(Color::WHITE),
-                border_color: Color::BLACK,
-                border_width: 1.0,
-                border_radius: 1.0,
-            }
-        }
-        fn focused(&self) -> text_input::Style {
-            text_input::Style {
-                background: Background::Color(Color {
-                    r: 1.0,
-                    g: 1.0,
-                    b: 0.878,
-                    a: 1.0,
-                }),
-                border_color: Color {
-                    r: 1.0,
-                    g: 0.0,
-                    b: 0.0,
-                    a: 1.0,
-                },
-                border_width: 1.0,
-                border_radius: 1.0,
-            }
-        }
-        fn placeholder_color(&self) -> Color {
-            Color {
-                r: 0.753,
-                g: 0.753,
-                b: 0.753,
-                a: 1.0,
-            }
-        }
-        fn value_color(&self) -> Color {
-            Color::BLACK
-        }
-        fn selection_color(&self) -> Color {
-            Color::WHITE
-        }
-    }
-}
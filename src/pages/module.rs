@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use iced::{
+    button, scrollable, Button, Column, Command, Container, Element, Length, Row, Scrollable, Text,
+};
+
+use crate::data::{Data, DataItems, FetchStatus};
+use crate::message::Message;
+use crate::module::Module;
+use crate::pages::Page;
+use crate::resource::{
+    resource_buttons, ResourceButtons, ResourceMessage, ResourceState, ResourceType,
+};
+use crate::theme::{self, Theme};
+
+#[derive(Debug, Clone)]
+pub struct ModulePage {
+    back_button: button::State,
+    refresh_button: button::State,
+    sync_button: button::State,
+    open_folder_button: button::State,
+    // Button states for the displayed files, multimedia, weblectures and conferences.
+    rows: [Vec<ResourceButtons>; 4],
+    scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum ModulePageMessage {
+    Back,
+    Refresh(String),
+    Sync(String),
+    OpenFolder(String),
+    ResourceMessage(ResourceType, String, PathBuf, ResourceMessage),
+}
+
+impl ModulePage {
+    pub fn default() -> Self {
+        ModulePage {
+            back_button: button::State::new(),
+            refresh_button: button::State::new(),
+            sync_button: button::State::new(),
+            open_folder_button: button::State::new(),
+            rows: Default::default(),
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: ModulePageMessage) -> Command<Message> {
+        match message {
+            ModulePageMessage::Back => {
+                Command::perform(async { Page::Modules }, Message::SwitchPage)
+            }
+            ModulePageMessage::Refresh(module_id) => {
+                Command::perform(async { module_id }, Message::LoadModuleResources)
+            }
+            ModulePageMessage::Sync(module_id) => {
+                Command::perform(async { module_id }, Message::SyncModule)
+            }
+            ModulePageMessage::OpenFolder(module_id) => {
+                Command::perform(async { module_id }, Message::OpenModuleFolder)
+            }
+            ModulePageMessage::ResourceMessage(resource_type, module_id, path, message) => {
+                Command::perform(
+                    async move { (resource_type, module_id, path, message) },
+                    Message::ResourceMessage,
+                )
+            }
+        }
+    }
+
+    pub fn view<'a>(
+        &'a mut self,
+        module_id: &str,
+        data: &Data,
+        modules_map: &'a HashMap<String, Module>,
+        theme: Theme,
+    ) -> Element<'a, ModulePageMessage> {
+        let title = match modules_map.get(module_id) {
+            Some(module) => format!("{} {}", module.code, module.name),
+            None => "Unknown module".to_string(),
+        };
+
+        let fetching = [
+            &data.files,
+            &data.multimedia,
+            &data.weblectures,
+            &data.conferences,
+        ]
+        .iter()
+        .any(|items| matches!(items.fetch_status, FetchStatus::Fetching));
+
+        let back_button = Button::new(&mut self.back_button, Text::new("Back to modules"))
+            .style(theme::Button(theme))
+            .on_press(ModulePageMessage::Back);
+        let refresh_button: Button<_> = if fetching {
+            Button::new(&mut self.refresh_button, Text::new("Loading…"))
+        } else {
+            Button::new(&mut self.refresh_button, Text::new("Refresh"))
+                .on_press(ModulePageMessage::Refresh(module_id.to_string()))
+        }
+        .style(theme::Button(theme));
+        let sync_button = Button::new(&mut self.sync_button, Text::new("Download all"))
+            .style(theme::Button(theme))
+            .on_press(ModulePageMessage::Sync(module_id.to_string()));
+        let open_folder_button =
+            Button::new(&mut self.open_folder_button, Text::new("Open folder"))
+                .style(theme::Button(theme))
+                .on_press(ModulePageMessage::OpenFolder(module_id.to_string()));
+
+        let [files_rows, multimedia_rows, weblectures_rows, conferences_rows] = &mut self.rows;
+        let resources = Column::new().spacing(20);
+        let resources = view_resources(
+            resources,
+            module_id,
+            &data.files,
+            files_rows,
+            ResourceType::File,
+            theme,
+        );
+        let resources = view_resources(
+            resources,
+            module_id,
+            &data.multimedia,
+            multimedia_rows,
+            ResourceType::Multimedia,
+            theme,
+        );
+        let resources = view_resources(
+            resources,
+            module_id,
+            &data.weblectures,
+            weblectures_rows,
+            ResourceType::Weblecture,
+            theme,
+        );
+        let resources = view_resources(
+            resources,
+            module_id,
+            &data.conferences,
+            conferences_rows,
+            ResourceType::Conference,
+            theme,
+        );
+
+        let content = Column::new()
+            .spacing(20)
+            .push(Text::new(title).size(28))
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(back_button)
+                    .push(refresh_button)
+                    .push(sync_button)
+                    .push(open_folder_button),
+            )
+            .push(resources);
+
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
+
+        Container::new(scrollable).height(Length::Fill).into()
+    }
+}
+
+fn view_resources<'a>(
+    column: Column<'a, ModulePageMessage>,
+    module_id: &str,
+    data: &DataItems<ResourceState>,
+    rows: &'a mut Vec<ResourceButtons>,
+    resource_type: ResourceType,
+    theme: Theme,
+) -> Column<'a, ModulePageMessage> {
+    let resources = data
+        .items
+        .iter()
+        .filter(|file| file.module_id == module_id)
+        .collect::<Vec<_>>();
+
+    let column =
+        column.push(Text::new(format!("{} ({})", resource_type.name(), resources.len())).size(24));
+
+    if resources.is_empty() {
+        column.push(Text::new("None found"))
+    } else {
+        let rows = resource_buttons(rows, resources.len());
+        resources
+            .into_iter()
+            .zip(rows.iter_mut())
+            .fold(column, |column, (file, buttons)| {
+                let resource_module_id = file.module_id.clone();
+                let resource_path = file.path.clone();
+                let label = file.path.display().to_string();
+                column.push(
+                    file.view_with_label(buttons, label, theme)
+                        .map(move |message| {
+                            ModulePageMessage::ResourceMessage(
+                                resource_type,
+                                resource_module_id.clone(),
+                                resource_path.clone(),
+                                message,
+                            )
+                        }),
+                )
+            })
+    }
+}
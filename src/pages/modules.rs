@@ -1,14 +1,17 @@
 use iced::{
-    button, scrollable, Button, Column, Command, Container, Element, Length, Scrollable, Text,
+    button, scrollable, Button, Column, Command, Container, Element, Length, Row, Scrollable, Text,
 };
 
-use crate::data::{DataItems, FetchStatus};
+use crate::data::{Data, FetchStatus};
 use crate::message::Message;
-use crate::module::{Module, ModuleMessage};
+use crate::module::ModuleMessage;
+use crate::pages::Page;
+use crate::theme::{self, Theme};
 
 #[derive(Debug, Clone)]
 pub struct ModulesPage {
     refresh_button: button::State,
+    mark_seen_button: button::State,
     scroll: scrollable::State,
 }
 
@@ -16,6 +19,7 @@ impl ModulesPage {
     pub fn default() -> Self {
         Self {
             refresh_button: button::State::new(),
+            mark_seen_button: button::State::new(),
             scroll: scrollable::State::new(),
         }
     }
@@ -23,31 +27,62 @@ impl ModulesPage {
     pub fn update(&mut self, message: ModuleMessage) -> Command<Message> {
         match message {
             ModuleMessage::RefreshModules => Command::perform(async {}, Message::LoadModules),
+            ModuleMessage::MarkAllSeen => Command::perform(async { None }, Message::MarkSeen),
+            ModuleMessage::OpenModule(module_id) => {
+                Command::perform(async { Page::Module(module_id) }, Message::SwitchPage)
+            }
         }
     }
 
-    pub fn view<'a>(&'a mut self, data: &'a DataItems<Module>) -> Element<'a, ModuleMessage> {
-        let modules: Element<_> = if data.items.len() > 0 {
+    pub fn view<'a>(&'a mut self, data: &'a mut Data, theme: Theme) -> Element<'a, ModuleMessage> {
+        let unseen_counts = data.unseen_counts_by_module();
+        let watched_counts = data.watched_counts_by_module();
+        let modules: Element<_> = if data.modules.items.len() > 0 {
             let col = Column::new().spacing(20);
-            data.items
-                .iter()
+            data.modules
+                .items
+                .iter_mut()
                 .filter(|m| m.is_taking)
-                .fold(col, |column, module| column.push(module.view()))
+                .fold(col, |column, module| {
+                    let watched = watched_counts
+                        .get(&module.id)
+                        .map_or(&[][..], |counts| &counts[..]);
+                    column.push(module.view(
+                        *unseen_counts.get(&module.id).unwrap_or(&0),
+                        watched,
+                        theme,
+                    ))
+                })
                 .into()
         } else {
             Text::new("No modules found").into()
         };
 
-        let refresh_button: Button<_> = match data.fetch_status {
+        let refresh_button: Button<_> = match data.modules.fetch_status {
             FetchStatus::Fetching => Button::new(&mut self.refresh_button, Text::new("Loading…")),
             _ => Button::new(&mut self.refresh_button, Text::new("Refresh"))
                 .on_press(ModuleMessage::RefreshModules),
-        };
+        }
+        .style(theme::Button(theme));
+
+        let mark_seen_button =
+            Button::new(&mut self.mark_seen_button, Text::new("Mark all as seen"))
+                .style(theme::Button(theme))
+                .on_press(ModuleMessage::MarkAllSeen);
 
-        let content = Column::new().spacing(20).push(refresh_button).push(modules);
+        let content = Column::new()
+            .spacing(20)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(refresh_button)
+                    .push(mark_seen_button),
+            )
+            .push(modules);
 
-        let scrollable =
-            Scrollable::new(&mut self.scroll).push(Container::new(content).width(Length::Fill));
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
 
         Container::new(scrollable).height(Length::Fill).into()
     }
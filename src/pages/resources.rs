@@ -1,36 +1,114 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use iced::{
-    button, scrollable, Button, Column, Command, Container, Element, Length, Scrollable, Text,
+    button, pick_list, scrollable, Align, Button, Checkbox, Column, Command, Container, Element,
+    Length, PickList, Row, Scrollable, Text,
 };
 
-use chrono::offset::Utc;
-use chrono::DateTime;
-
 use crate::data::{DataItems, FetchStatus};
+use crate::filter::{ResourceFilter, ResourceFilterMessage};
 use crate::message::Message;
 use crate::module::Module;
-use crate::resource::{ResourceMessage, ResourceState, ResourceType};
+use crate::resource::{
+    resource_buttons, ResourceButtons, ResourceMessage, ResourceState, ResourceType,
+};
+use crate::sort::ResourceSort;
+use crate::theme::{self, Theme};
+use crate::utils::format_time;
+
+mod table;
+mod tree;
+
+use table::ResourceTable;
+use tree::FileTree;
 
 #[derive(Debug, Clone)]
 pub struct ResourcesPage {
     resource_type: ResourceType,
     refresh_button: button::State,
+    rescan_button: button::State,
+    mark_seen_button: button::State,
+    archive_button: button::State,
+    hide_removed: bool,
+    filter: ResourceFilter,
+    layout: Layout,
+    layout_list: pick_list::State<Layout>,
+    tree: FileTree,
+    table: ResourceTable,
+    selection_anchor: Option<(String, PathBuf)>,
+    cursor: Option<(String, PathBuf)>,
+    download_selected_button: button::State,
+    delete_selected_button: button::State,
+    mark_seen_selected_button: button::State,
+    open_selected_button: button::State,
+    page: usize,
+    previous_page_button: button::State,
+    next_page_button: button::State,
+    rows: Vec<ResourceButtons>,
     scroll: scrollable::State,
 }
 
+// Number of rows displayed on each page, which includes folders in the folder layout.
+const PAGE_SIZE: usize = 100;
+
 #[derive(Debug, Clone)]
 pub enum ResourcesMessage {
     Refresh,
+    Rescan,
+    MarkAllSeen,
+    ToggleHideRemoved(bool),
+    ArchiveRemoved,
+    Filter(ResourceFilterMessage),
+    LayoutSelected(Layout),
+    Sort(ResourceSort),
+    PageChanged(usize),
+    ToggleFolder(String, PathBuf),
+    DownloadFolder(String, PathBuf),
+    OpenFolder(String, PathBuf),
+    Select(String, PathBuf, bool),
+    SelectAll(bool),
+    Batch(BatchAction),
     ResourceMessage(ResourceType, String, PathBuf, ResourceMessage),
 }
 
+#[derive(Debug, Copy, Clone)]
+pub enum BatchAction {
+    Download,
+    DeleteLocal,
+    MarkSeen,
+    OpenFolder,
+}
+
 impl ResourcesPage {
     pub fn default(resource_type: ResourceType) -> Self {
         ResourcesPage {
             resource_type,
             refresh_button: button::State::new(),
+            rescan_button: button::State::new(),
+            mark_seen_button: button::State::new(),
+            archive_button: button::State::new(),
+            hide_removed: false,
+            filter: ResourceFilter::default(),
+            // Workbin files are displayed in their folders by default.
+            layout: match resource_type {
+                ResourceType::File => Layout::Folders,
+                _ => Layout::List,
+            },
+            layout_list: pick_list::State::default(),
+            tree: FileTree::default(resource_type),
+            table: ResourceTable::default(resource_type),
+            selection_anchor: None,
+            cursor: None,
+            download_selected_button: button::State::new(),
+            delete_selected_button: button::State::new(),
+            mark_seen_selected_button: button::State::new(),
+            open_selected_button: button::State::new(),
+            page: 0,
+            previous_page_button: button::State::new(),
+            next_page_button: button::State::new(),
+            rows: vec![],
             scroll: scrollable::State::new(),
         }
     }
@@ -41,6 +119,84 @@ impl ResourcesPage {
                 let resource_type = self.resource_type;
                 Command::perform(async move { resource_type }, Message::LoadResources)
             }
+            ResourcesMessage::Rescan => {
+                let resource_type = self.resource_type;
+                Command::perform(async move { resource_type }, Message::ReconcileResources)
+            }
+            ResourcesMessage::MarkAllSeen => {
+                let resource_type = self.resource_type;
+                Command::perform(async move { Some(resource_type) }, Message::MarkSeen)
+            }
+            ResourcesMessage::ToggleHideRemoved(hide_removed) => {
+                self.hide_removed = hide_removed;
+                Command::none()
+            }
+            ResourcesMessage::ArchiveRemoved => {
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { resource_type },
+                    Message::ArchiveRemovedResources,
+                )
+            }
+            ResourcesMessage::Filter(message) => {
+                self.filter.update(message);
+                self.page = 0;
+                Command::none()
+            }
+            ResourcesMessage::LayoutSelected(layout) => {
+                self.layout = layout;
+                self.page = 0;
+                Command::none()
+            }
+            ResourcesMessage::Sort(sort) => {
+                self.page = 0;
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { (resource_type, sort) },
+                    Message::ResourceSortChanged,
+                )
+            }
+            ResourcesMessage::PageChanged(page) => {
+                self.page = page;
+                self.scroll = scrollable::State::new();
+                Command::none()
+            }
+            ResourcesMessage::ToggleFolder(module_id, folder) => {
+                self.tree.toggle(module_id, folder);
+                Command::none()
+            }
+            ResourcesMessage::DownloadFolder(module_id, folder) => {
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { (resource_type, module_id, folder) },
+                    Message::DownloadFolder,
+                )
+            }
+            ResourcesMessage::OpenFolder(module_id, folder) => {
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { (resource_type, module_id, folder) },
+                    Message::OpenFolder,
+                )
+            }
+            ResourcesMessage::Select(module_id, path, selected) => {
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { (resource_type, module_id, path, selected) },
+                    Message::SelectResource,
+                )
+            }
+            ResourcesMessage::SelectAll(selected) => {
+                let resource_type = self.resource_type;
+                Command::perform(
+                    async move { (resource_type, selected) },
+                    Message::SelectAllResources,
+                )
+            }
+            ResourcesMessage::Batch(action) => {
+                let resource_type = self.resource_type;
+                Command::perform(async move { (resource_type, action) }, Message::BatchAction)
+            }
             ResourcesMessage::ResourceMessage(resource_type, module_id, path, message) => {
                 Command::perform(
                     async move { (resource_type, module_id, path, message) },
@@ -50,29 +206,232 @@ impl ResourcesPage {
         }
     }
 
+    pub fn set_selection_anchor(&mut self, module_id: String, path: PathBuf) {
+        self.selection_anchor = Some((module_id, path));
+    }
+
+    // The resource under the keyboard cursor, if it is displayed on the current page.
+    pub fn displayed_cursor(
+        &self,
+        items: &[ResourceState],
+        modules_map: &HashMap<String, Module>,
+        sort: ResourceSort,
+    ) -> Option<(String, PathBuf)> {
+        let (module_id, path) = self.cursor.as_ref()?;
+        let files = self.visible_files(items, modules_map, sort);
+        let page = self.page.min(self.page_count(&files).saturating_sub(1));
+
+        self.file_rows(&files)
+            .into_iter()
+            .find(|(position, _)| {
+                files[*position].module_id == *module_id && files[*position].path == *path
+            })
+            .filter(|(_, row)| row / PAGE_SIZE == page)
+            .map(|_| (module_id.clone(), path.clone()))
+    }
+
+    // Moves the keyboard cursor up or down the displayed resources, skipping those within
+    // collapsed folders and moving to the page containing it.
+    pub fn move_cursor(
+        &mut self,
+        items: &[ResourceState],
+        modules_map: &HashMap<String, Module>,
+        sort: ResourceSort,
+        down: bool,
+    ) {
+        let files = self.visible_files(items, modules_map, sort);
+        let file_rows = self.file_rows(&files);
+        let index = match &self.cursor {
+            Some((module_id, path)) => file_rows.iter().position(|(position, _)| {
+                files[*position].module_id == *module_id && files[*position].path == *path
+            }),
+            None => None,
+        };
+        let index = match index {
+            Some(index) if down => (index + 1).min(file_rows.len().saturating_sub(1)),
+            Some(index) => index.saturating_sub(1),
+            None => 0,
+        };
+
+        if let Some((position, row)) = file_rows.get(index) {
+            let file = files[*position];
+            self.cursor = Some((file.module_id.clone(), file.path.clone()));
+            self.page = row / PAGE_SIZE;
+        } else {
+            self.cursor = None;
+        }
+    }
+
+    // The position of each displayed resource along with the row displaying it.
+    fn file_rows(&self, files: &[&ResourceState]) -> Vec<(usize, usize)> {
+        if self.layout == Layout::Folders {
+            // Expand all folders when filtering, so that all matching files are shown.
+            self.tree.file_rows(files, self.filter.is_active())
+        } else {
+            (0..files.len())
+                .map(|position| (position, position))
+                .collect()
+        }
+    }
+
+    fn page_count(&self, files: &[&ResourceState]) -> usize {
+        let row_count = if self.layout == Layout::Folders {
+            self.tree.row_count(files, self.filter.is_active())
+        } else {
+            files.len()
+        };
+
+        (row_count + PAGE_SIZE - 1) / PAGE_SIZE
+    }
+
+    // The resources displayed on the page, in the order they are displayed.
+    pub fn visible_files<'a>(
+        &self,
+        items: &'a [ResourceState],
+        modules_map: &HashMap<String, Module>,
+        sort: ResourceSort,
+    ) -> Vec<&'a ResourceState> {
+        let mut files = items
+            .iter()
+            .filter(|file| !(self.hide_removed && file.removed))
+            .filter(|file| self.filter.matches(file, modules_map))
+            .collect::<Vec<_>>();
+
+        if self.layout == Layout::Table {
+            files.sort_by(|r1, r2| sort.compare(r1, r2, modules_map));
+        }
+
+        files
+    }
+
+    // The displayed resources between the previously selected resource and the given one.
+    pub fn selection_range(
+        &self,
+        items: &[ResourceState],
+        modules_map: &HashMap<String, Module>,
+        sort: ResourceSort,
+        module_id: &str,
+        path: &Path,
+    ) -> Vec<(String, PathBuf)> {
+        match &self.selection_anchor {
+            Some((anchor_module_id, anchor_path)) => {
+                let files = self.visible_files(items, modules_map, sort);
+                let position = |module_id: &str, path: &Path| {
+                    files
+                        .iter()
+                        .position(|file| file.module_id == module_id && file.path == path)
+                };
+
+                match (
+                    position(anchor_module_id, anchor_path),
+                    position(module_id, path),
+                ) {
+                    (Some(start), Some(end)) => files[start.min(end)..=start.max(end)]
+                        .iter()
+                        .map(|file| (file.module_id.clone(), file.path.clone()))
+                        .collect(),
+                    _ => vec![],
+                }
+            }
+            None => vec![],
+        }
+    }
+
     pub fn view<'a>(
         &'a mut self,
-        data: &'a mut DataItems<ResourceState>,
+        data: &DataItems<ResourceState>,
         modules_map: &'a HashMap<String, Module>,
+        sort: ResourceSort,
+        theme: Theme,
     ) -> Element<'a, ResourcesMessage> {
-        let files: Element<_> = if data.items.len() > 0 {
-            data.items
-                .iter_mut()
-                .fold(Column::new().spacing(20), |column, file| {
-                    // TODO: figure out Rust move semantics here
-                    let resource_type = self.resource_type;
-                    let resource_module_id = file.module_id.clone();
-                    let resource_path = file.path.clone();
-                    column.push(file.view(modules_map, resource_type).map(move |message| {
-                        ResourcesMessage::ResourceMessage(
-                            resource_type,
-                            resource_module_id.clone(),
-                            resource_path.clone(),
-                            message,
-                        )
-                    }))
-                })
-                .into()
+        let hide_removed = self.hide_removed;
+        let removed_count = data.items.iter().filter(|file| file.removed).count();
+        let has_removed_downloads = data
+            .items
+            .iter()
+            .any(|file| file.removed && file.download_path.is_some());
+
+        let total_count = data.items.len();
+        let filter = &self.filter;
+        let matching_count = data
+            .items
+            .iter()
+            .filter(|file| filter.matches(file, modules_map))
+            .count();
+
+        let displayed_files = self.visible_files(&data.items, modules_map, sort);
+        let all_selected =
+            !displayed_files.is_empty() && displayed_files.iter().all(|file| file.selected);
+        let selected_count = data.items.iter().filter(|file| file.selected).count();
+
+        // Only a page of rows is displayed at a time.
+        let page_count = self.page_count(&displayed_files);
+        let page = self.page.min(page_count.saturating_sub(1));
+        let paginated = page_count > 1;
+
+        let resource_type = self.resource_type;
+        let files: Element<_> = if total_count > 0 && self.layout == Layout::Folders {
+            self.tree.view(
+                displayed_files,
+                &mut self.rows,
+                page,
+                modules_map,
+                filter.is_active(),
+                &self.cursor,
+                theme,
+            )
+        } else if total_count > 0 {
+            let page_files = displayed_files
+                .into_iter()
+                .skip(page * PAGE_SIZE)
+                .take(PAGE_SIZE)
+                .collect::<Vec<_>>();
+            let rows = resource_buttons(&mut self.rows, page_files.len());
+
+            match self.layout {
+                Layout::Table => self.table.view(
+                    page_files,
+                    rows,
+                    modules_map,
+                    sort,
+                    all_selected,
+                    &self.cursor,
+                    theme,
+                ),
+                _ => {
+                    let cursor = &self.cursor;
+                    page_files
+                        .into_iter()
+                        .zip(rows.iter_mut())
+                        .fold(Column::new().spacing(20), |column, (file, buttons)| {
+                            let resource_module_id = file.module_id.clone();
+                            let resource_path = file.path.clone();
+                            let content =
+                                file.view(buttons, modules_map, resource_type, theme).map({
+                                    let resource_module_id = resource_module_id.clone();
+                                    let resource_path = resource_path.clone();
+                                    move |message| {
+                                        ResourcesMessage::ResourceMessage(
+                                            resource_type,
+                                            resource_module_id.clone(),
+                                            resource_path.clone(),
+                                            message,
+                                        )
+                                    }
+                                });
+
+                            column.push(selectable(
+                                cursor,
+                                file.selected,
+                                resource_module_id,
+                                resource_path,
+                                content,
+                                theme,
+                            ))
+                        })
+                        .into()
+                }
+            }
         } else {
             let type_text = match data.fetch_status {
                 FetchStatus::Idle => match self.resource_type {
@@ -92,21 +451,233 @@ impl ResourcesPage {
             FetchStatus::Fetching => Button::new(&mut self.refresh_button, Text::new("Loading…")),
             _ => Button::new(&mut self.refresh_button, Text::new("Refresh"))
                 .on_press(ResourcesMessage::Refresh),
+        }
+        .style(theme::Button(theme));
+
+        let rescan_button = Button::new(&mut self.rescan_button, Text::new("Rescan local files"))
+            .style(theme::Button(theme))
+            .on_press(ResourcesMessage::Rescan);
+        let mark_seen_button =
+            Button::new(&mut self.mark_seen_button, Text::new("Mark all as seen"))
+                .style(theme::Button(theme))
+                .on_press(ResourcesMessage::MarkAllSeen);
+
+        let last_updated = Text::new(format!(
+            "Last updated at {}",
+            format_time(data.last_updated)
+        ));
+
+        let removed_details: Element<_> = if removed_count > 0 {
+            let archive_button =
+                Button::new(&mut self.archive_button, Text::new("Archive removed files"))
+                    .style(theme::Button(theme));
+            let archive_button = if has_removed_downloads {
+                archive_button.on_press(ResourcesMessage::ArchiveRemoved)
+            } else {
+                archive_button
+            };
+
+            Row::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(Text::new(format!("{} removed from LumiNUS", removed_count)))
+                .push(
+                    Checkbox::new(
+                        hide_removed,
+                        "Hide removed",
+                        ResourcesMessage::ToggleHideRemoved,
+                    )
+                    .style(theme::Checkbox(theme)),
+                )
+                .push(archive_button)
+                .into()
+        } else {
+            Column::new().into()
+        };
+
+        // Only workbin files are organised into folders.
+        let layouts = match self.resource_type {
+            ResourceType::File => &Layout::FILE_LAYOUTS[..],
+            _ => &Layout::VIDEO_LAYOUTS[..],
         };
+        let layout_list = PickList::new(
+            &mut self.layout_list,
+            layouts,
+            Some(self.layout),
+            ResourcesMessage::LayoutSelected,
+        )
+        .style(theme::PickList(theme));
 
-        let last_updated: DateTime<Utc> = data.last_updated.into();
-        let last_updated = last_updated.format("%d/%m/%Y %T");
-        let last_updated = Text::new(format!("Last updated at {}", last_updated));
+        let selection_details: Element<_> = if selected_count > 0 {
+            Row::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(Text::new(format!("{} selected", selected_count)))
+                .push(
+                    Button::new(&mut self.download_selected_button, Text::new("Download"))
+                        .style(theme::Button(theme))
+                        .on_press(ResourcesMessage::Batch(BatchAction::Download)),
+                )
+                .push(
+                    Button::new(
+                        &mut self.delete_selected_button,
+                        Text::new("Delete local copies"),
+                    )
+                    .style(theme::Button(theme))
+                    .on_press(ResourcesMessage::Batch(BatchAction::DeleteLocal)),
+                )
+                .push(
+                    Button::new(
+                        &mut self.mark_seen_selected_button,
+                        Text::new("Mark as seen"),
+                    )
+                    .style(theme::Button(theme))
+                    .on_press(ResourcesMessage::Batch(BatchAction::MarkSeen)),
+                )
+                .push(
+                    Button::new(
+                        &mut self.open_selected_button,
+                        Text::new("Open containing folders"),
+                    )
+                    .style(theme::Button(theme))
+                    .on_press(ResourcesMessage::Batch(BatchAction::OpenFolder)),
+                )
+                .into()
+        } else {
+            Column::new().into()
+        };
+
+        let pagination: Element<_> = if paginated {
+            let previous_button =
+                Button::new(&mut self.previous_page_button, Text::new("Previous"))
+                    .style(theme::Button(theme));
+            let previous_button = if page > 0 {
+                previous_button.on_press(ResourcesMessage::PageChanged(page - 1))
+            } else {
+                previous_button
+            };
+            let next_button = Button::new(&mut self.next_page_button, Text::new("Next"))
+                .style(theme::Button(theme));
+            let next_button = if page + 1 < page_count {
+                next_button.on_press(ResourcesMessage::PageChanged(page + 1))
+            } else {
+                next_button
+            };
+
+            Row::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(previous_button)
+                .push(Text::new(format!("Page {} of {}", page + 1, page_count)))
+                .push(next_button)
+                .into()
+        } else {
+            Column::new().into()
+        };
+
+        let filter_details: Element<_> = if self.filter.is_active() {
+            Text::new(format!("{} of {} matching", matching_count, total_count)).into()
+        } else {
+            Column::new().into()
+        };
 
         let content = Column::new()
             .spacing(20)
-            .push(refresh_button)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(refresh_button)
+                    .push(rescan_button)
+                    .push(mark_seen_button)
+                    .push(layout_list),
+            )
             .push(last_updated)
-            .push(files);
+            .push(self.filter.view(theme).map(ResourcesMessage::Filter))
+            .push(filter_details)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .align_items(Align::Center)
+                    .push(
+                        Checkbox::new(
+                            all_selected,
+                            if self.filter.is_active() {
+                                "Select all matching"
+                            } else {
+                                "Select all"
+                            },
+                            ResourcesMessage::SelectAll,
+                        )
+                        .style(theme::Checkbox(theme)),
+                    )
+                    .push(selection_details),
+            )
+            .push(removed_details)
+            .push(files)
+            .push(pagination);
 
-        let scrollable =
-            Scrollable::new(&mut self.scroll).push(Container::new(content).width(Length::Fill));
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
 
         Container::new(scrollable).height(Length::Fill).into()
     }
 }
+
+// Prefixes a resource row with a checkbox for selecting it, and a marker if the row has
+// been moved to using the keyboard.
+fn selectable<'a>(
+    cursor: &Option<(String, PathBuf)>,
+    selected: bool,
+    module_id: String,
+    path: PathBuf,
+    content: Element<'a, ResourcesMessage>,
+    theme: Theme,
+) -> Row<'a, ResourcesMessage> {
+    let focused = match cursor {
+        Some((cursor_module_id, cursor_path)) => {
+            *cursor_module_id == module_id && *cursor_path == path
+        }
+        None => false,
+    };
+
+    Row::new()
+        .spacing(20)
+        .align_items(Align::Center)
+        .push(Text::new(if focused { ">" } else { "" }).width(Length::Units(CURSOR_WIDTH)))
+        .push(
+            Checkbox::new(selected, "", move |selected| {
+                ResourcesMessage::Select(module_id.clone(), path.clone(), selected)
+            })
+            .style(theme::Checkbox(theme)),
+        )
+        .push(content)
+}
+
+const CURSOR_WIDTH: u16 = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Layout {
+    List,
+    Folders,
+    Table,
+}
+
+impl Layout {
+    const FILE_LAYOUTS: [Layout; 3] = [Layout::List, Layout::Folders, Layout::Table];
+    const VIDEO_LAYOUTS: [Layout; 2] = [Layout::List, Layout::Table];
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Layout::List => "List",
+                Layout::Folders => "Folders",
+                Layout::Table => "Table",
+            }
+        )
+    }
+}
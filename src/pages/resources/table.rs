@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use iced::{button, Align, Button, Checkbox, Column, Element, Length, Row, Space, Text};
+
+use crate::module::Module;
+use crate::pages::resources::{selectable, ResourcesMessage, CURSOR_WIDTH};
+use crate::resource::{ResourceButtons, ResourceState, ResourceType, ACTIONS_WIDTH};
+use crate::sort::{ResourceSort, SortColumn};
+use crate::theme::{self, Theme};
+use crate::utils::{format_size, format_time};
+
+#[derive(Debug, Clone)]
+pub struct ResourceTable {
+    resource_type: ResourceType,
+    header_buttons: [button::State; 7],
+}
+
+impl ResourceTable {
+    pub fn default(resource_type: ResourceType) -> Self {
+        ResourceTable {
+            resource_type,
+            header_buttons: Default::default(),
+        }
+    }
+
+    // Renders files as rows of a table. The files are expected to be sorted already.
+    pub fn view<'a>(
+        &'a mut self,
+        files: Vec<&ResourceState>,
+        rows: &'a mut [ResourceButtons],
+        modules_map: &'a HashMap<String, Module>,
+        sort: ResourceSort,
+        all_selected: bool,
+        cursor: &Option<(String, PathBuf)>,
+        theme: Theme,
+    ) -> Element<'a, ResourcesMessage> {
+        let resource_type = self.resource_type;
+        let header = self
+            .header_buttons
+            .iter_mut()
+            .zip(SortColumn::ALL.iter())
+            .fold(
+                Row::new()
+                    .height(Length::Units(30))
+                    .align_items(Align::Center)
+                    .spacing(20)
+                    .push(Space::with_width(Length::Units(CURSOR_WIDTH)))
+                    .push(
+                        Checkbox::new(all_selected, "", ResourcesMessage::SelectAll)
+                            .style(theme::Checkbox(theme)),
+                    ),
+                |row, (state, column)| {
+                    let title = if sort.column == *column {
+                        format!(
+                            "{} {}",
+                            column.title(),
+                            if sort.ascending { "^" } else { "v" }
+                        )
+                    } else {
+                        column.title().to_string()
+                    };
+
+                    row.push(
+                        Button::new(state, Text::new(title))
+                            .style(theme::Button(theme))
+                            .width(column_width(*column))
+                            .on_press(ResourcesMessage::Sort(sort.toggle(*column))),
+                    )
+                },
+            )
+            .push(Space::with_width(Length::Units(ACTIONS_WIDTH)));
+
+        files
+            .into_iter()
+            .zip(rows.iter_mut())
+            .fold(
+                Column::new().spacing(10).push(header),
+                |column, (file, buttons)| {
+                    let cells = SortColumn::ALL
+                        .iter()
+                        .map(|sort_column| {
+                            (
+                                cell_text(file, *sort_column, resource_type, modules_map),
+                                column_width(*sort_column),
+                            )
+                        })
+                        .collect();
+
+                    let module_id = file.module_id.clone();
+                    let path = file.path.clone();
+                    let selected = file.selected;
+                    let content = file.view_cells(buttons, cells, theme).map({
+                        let module_id = module_id.clone();
+                        let path = path.clone();
+                        move |message| {
+                            ResourcesMessage::ResourceMessage(
+                                resource_type,
+                                module_id.clone(),
+                                path.clone(),
+                                message,
+                            )
+                        }
+                    });
+
+                    column.push(selectable(
+                        cursor, selected, module_id, path, content, theme,
+                    ))
+                },
+            )
+            .into()
+    }
+}
+
+fn cell_text(
+    file: &ResourceState,
+    column: SortColumn,
+    resource_type: ResourceType,
+    modules_map: &HashMap<String, Module>,
+) -> String {
+    match column {
+        SortColumn::Name => file.name(),
+        SortColumn::Module => match modules_map.get(&file.module_id) {
+            Some(module) => module.code.clone(),
+            None => "Unknown".to_string(),
+        },
+        SortColumn::Type => {
+            let file_type = file.file_type();
+            if file_type.is_empty() {
+                resource_type.name().to_string()
+            } else {
+                file_type
+            }
+        }
+        SortColumn::LastUpdated => format_time(file.last_updated),
+        SortColumn::DownloadTime => match file.download_time {
+            Some(download_time) => format_time(download_time),
+            None => "—".to_string(),
+        },
+        // The size is only known once the resource has been downloaded.
+        SortColumn::Size => match file.download_size {
+            Some(size) => format_size(size),
+            None => "—".to_string(),
+        },
+        SortColumn::Status => file.status().to_string(),
+    }
+}
+
+fn column_width(column: SortColumn) -> Length {
+    match column {
+        SortColumn::Name => Length::FillPortion(3),
+        SortColumn::Module => Length::Units(100),
+        SortColumn::Type => Length::Units(100),
+        SortColumn::LastUpdated => Length::Units(170),
+        SortColumn::DownloadTime => Length::Units(170),
+        SortColumn::Size => Length::Units(90),
+        SortColumn::Status => Length::Units(130),
+    }
+}
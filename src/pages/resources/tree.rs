@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use iced::{button, Align, Button, Column, Element, Length, Row, Space, Text};
+
+use crate::module::Module;
+use crate::pages::resources::{selectable, ResourcesMessage, PAGE_SIZE};
+use crate::resource::{resource_buttons, ResourceButtons, ResourceState, ResourceType};
+use crate::theme::{self, Theme};
+
+// A folder is identified by its module ID and its path within the module, with the
+// module itself represented by an empty path.
+type FolderKey = (String, PathBuf);
+
+#[derive(Debug, Clone)]
+pub struct FileTree {
+    resource_type: ResourceType,
+    expanded: HashSet<FolderKey>,
+    folders: Vec<(FolderKey, FolderState)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FolderState {
+    toggle_button: button::State,
+    download_button: button::State,
+    open_button: button::State,
+}
+
+enum TreeEntry {
+    Folder(FolderKey, FolderEntry),
+    File(usize, bool),
+}
+
+#[derive(Copy, Clone)]
+struct FolderEntry {
+    depth: usize,
+    expanded: bool,
+    // Only the outermost folder students can upload submissions to is marked.
+    uploadable: bool,
+}
+
+impl FileTree {
+    pub fn default(resource_type: ResourceType) -> Self {
+        FileTree {
+            resource_type,
+            expanded: HashSet::new(),
+            folders: vec![],
+        }
+    }
+
+    pub fn toggle(&mut self, module_id: String, folder: PathBuf) {
+        let key = (module_id, folder);
+        if !self.expanded.remove(&key) {
+            self.expanded.insert(key);
+        }
+    }
+
+    // The number of rows displayed for the given files, counting folders and excluding
+    // files within collapsed folders.
+    pub fn row_count(&self, files: &[&ResourceState], expand_all: bool) -> usize {
+        self.entries(files, expand_all)
+            .iter()
+            .filter(|entry| entry.is_visible())
+            .count()
+    }
+
+    // The position of each displayed file along with the row displaying it, skipping
+    // files within collapsed folders.
+    pub fn file_rows(&self, files: &[&ResourceState], expand_all: bool) -> Vec<(usize, usize)> {
+        let mut file_rows = vec![];
+        let mut row = 0;
+        let mut position = 0;
+        for entry in self.entries(files, expand_all) {
+            if let TreeEntry::File(_, visible) = entry {
+                if visible {
+                    file_rows.push((position, row));
+                }
+                position += 1;
+            }
+            if entry.is_visible() {
+                row += 1;
+            }
+        }
+
+        file_rows
+    }
+
+    // Lays out files as a tree of modules and folders. The files are expected to be
+    // sorted by module ID and path, which keeps the contents of each folder together.
+    fn entries(&self, files: &[&ResourceState], expand_all: bool) -> Vec<TreeEntry> {
+        // Folders which only contain files within uploadable folders are uploadable. The
+        // module itself never is.
+        let mut uploadable: HashMap<FolderKey, bool> = HashMap::new();
+        for file in files.iter() {
+            for folder in file.path.ancestors().skip(1) {
+                if folder.as_os_str().is_empty() {
+                    continue;
+                }
+                *uploadable
+                    .entry((file.module_id.clone(), folder.to_path_buf()))
+                    .or_insert(true) &= file.metadata.in_uploadable_folder;
+            }
+        }
+        let is_uploadable = |key: &FolderKey| uploadable.get(key).copied().unwrap_or(false);
+
+        let mut entries = vec![];
+        // The folders containing the previous file, and whether each of them is expanded.
+        let mut ancestors: Vec<(FolderKey, bool)> = vec![];
+
+        for file in files.iter() {
+            let mut folders = vec![(file.module_id.clone(), PathBuf::new())];
+            if let Some(parent) = file.path.parent() {
+                let mut folder = PathBuf::new();
+                for component in parent.components() {
+                    folder.push(component);
+                    folders.push((file.module_id.clone(), folder.clone()));
+                }
+            }
+
+            let common = ancestors
+                .iter()
+                .zip(folders.iter())
+                .take_while(|(ancestor, folder)| ancestor.0 == **folder)
+                .count();
+            ancestors.truncate(common);
+
+            for key in folders.into_iter().skip(common) {
+                let visible = ancestors.iter().all(|(_, expanded)| *expanded);
+                let expanded = expand_all || self.expanded.contains(&key);
+                if visible {
+                    let uploadable = is_uploadable(&key)
+                        && !ancestors
+                            .iter()
+                            .any(|(ancestor, _)| is_uploadable(ancestor));
+                    entries.push(TreeEntry::Folder(
+                        key.clone(),
+                        FolderEntry {
+                            depth: ancestors.len(),
+                            expanded,
+                            uploadable,
+                        },
+                    ));
+                }
+                ancestors.push((key, expanded));
+            }
+
+            let visible = ancestors.iter().all(|(_, expanded)| *expanded);
+            entries.push(TreeEntry::File(ancestors.len(), visible));
+        }
+
+        entries
+    }
+
+    // Renders a page of the tree, which is split into pages by rows so that expanding
+    // large folders doesn't display every file at once.
+    pub fn view<'a>(
+        &'a mut self,
+        files: Vec<&ResourceState>,
+        rows: &'a mut Vec<ResourceButtons>,
+        page: usize,
+        modules_map: &'a HashMap<String, Module>,
+        expand_all: bool,
+        cursor: &Option<(String, PathBuf)>,
+        theme: Theme,
+    ) -> Element<'a, ResourcesMessage> {
+        // Only the files within the page are kept, alongside the entries displaying them.
+        let page_rows = page * PAGE_SIZE..(page + 1) * PAGE_SIZE;
+        let all_entries = self.entries(&files, expand_all);
+        let mut row = 0;
+        let mut files = files.into_iter();
+        let mut entries = vec![];
+        let mut page_files = vec![];
+        for entry in all_entries {
+            let file = match entry {
+                TreeEntry::File(_, _) => files.next(),
+                TreeEntry::Folder(_, _) => None,
+            };
+            if !entry.is_visible() {
+                continue;
+            }
+            if page_rows.contains(&row) {
+                if let Some(file) = file {
+                    page_files.push(file);
+                }
+                entries.push(entry);
+            }
+            row += 1;
+        }
+
+        // Button states are kept for each visible folder, in the order they are displayed.
+        let mut previous_folders: HashMap<FolderKey, FolderState> =
+            self.folders.drain(..).collect();
+        for entry in entries.iter() {
+            if let TreeEntry::Folder(key, _) = entry {
+                let state = previous_folders.remove(key).unwrap_or_default();
+                self.folders.push((key.clone(), state));
+            }
+        }
+
+        let resource_type = self.resource_type;
+        let mut folders = self.folders.iter_mut();
+        let rows = resource_buttons(rows, page_files.len());
+        let mut files = page_files.into_iter().zip(rows.iter_mut());
+
+        entries
+            .into_iter()
+            .fold(Column::new().spacing(10), |column, entry| match entry {
+                TreeEntry::Folder(_, folder_entry) => match folders.next() {
+                    Some(((module_id, folder), state)) => column.push(view_folder(
+                        module_id,
+                        folder,
+                        state,
+                        folder_entry,
+                        modules_map,
+                        theme,
+                    )),
+                    None => column,
+                },
+                TreeEntry::File(depth, _) => match files.next() {
+                    Some((file, buttons)) => {
+                        let module_id = file.module_id.clone();
+                        let path = file.path.clone();
+                        let name = file.name();
+                        let selected = file.selected;
+                        let content = file.view_with_label(buttons, name, theme).map({
+                            let module_id = module_id.clone();
+                            let path = path.clone();
+                            move |message| {
+                                ResourcesMessage::ResourceMessage(
+                                    resource_type,
+                                    module_id.clone(),
+                                    path.clone(),
+                                    message,
+                                )
+                            }
+                        });
+
+                        column.push(Row::new().push(Space::with_width(indent(depth))).push(
+                            selectable(cursor, selected, module_id, path, content, theme),
+                        ))
+                    }
+                    None => column,
+                },
+            })
+            .into()
+    }
+}
+
+impl TreeEntry {
+    fn is_visible(&self) -> bool {
+        match self {
+            TreeEntry::Folder(_, _) => true,
+            TreeEntry::File(_, visible) => *visible,
+        }
+    }
+}
+
+fn view_folder<'a>(
+    module_id: &str,
+    folder: &Path,
+    state: &'a mut FolderState,
+    entry: FolderEntry,
+    modules_map: &HashMap<String, Module>,
+    theme: Theme,
+) -> Element<'a, ResourcesMessage> {
+    let name = if folder.as_os_str().is_empty() {
+        match modules_map.get(module_id) {
+            Some(module) => format!("{} {}", module.code, module.name),
+            None => "Unknown".to_string(),
+        }
+    } else {
+        folder
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+
+    let toggle_button = Button::new(
+        &mut state.toggle_button,
+        Text::new(format!(
+            "{} {}",
+            if entry.expanded { "-" } else { "+" },
+            name
+        )),
+    )
+    .style(theme::Button(theme))
+    .on_press(ResourcesMessage::ToggleFolder(
+        module_id.to_string(),
+        folder.to_path_buf(),
+    ));
+    let download_button = Button::new(&mut state.download_button, Text::new("Download all"))
+        .style(theme::Button(theme))
+        .on_press(ResourcesMessage::DownloadFolder(
+            module_id.to_string(),
+            folder.to_path_buf(),
+        ));
+    let open_button = Button::new(&mut state.open_button, Text::new("Open folder"))
+        .style(theme::Button(theme))
+        .on_press(ResourcesMessage::OpenFolder(
+            module_id.to_string(),
+            folder.to_path_buf(),
+        ));
+
+    let row = Row::new()
+        .height(Length::Units(30))
+        .align_items(Align::Center)
+        .spacing(20)
+        .push(Space::with_width(indent(entry.depth)))
+        .push(toggle_button);
+
+    let row = if entry.uploadable {
+        row.push(Text::new("Uploadable"))
+    } else {
+        row
+    };
+
+    row.push(download_button).push(open_button).into()
+}
+
+fn indent(depth: usize) -> Length {
+    Length::Units(depth as u16 * 20)
+}
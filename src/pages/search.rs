@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use iced::{scrollable, Column, Command, Container, Element, Length, Scrollable, Text};
+
+use crate::data::{Data, DataItems};
+use crate::filter::{ResourceFilter, ResourceFilterMessage};
+use crate::message::Message;
+use crate::module::Module;
+use crate::resource::{
+    resource_buttons, ResourceButtons, ResourceMessage, ResourceState, ResourceType,
+};
+use crate::theme::{self, Theme};
+
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    filter: ResourceFilter,
+    // Button states for the matching files, multimedia, weblectures and conferences.
+    rows: [Vec<ResourceButtons>; 4],
+    scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchMessage {
+    Filter(ResourceFilterMessage),
+    ResourceMessage(ResourceType, String, PathBuf, ResourceMessage),
+}
+
+impl SearchPage {
+    pub fn default() -> Self {
+        SearchPage {
+            filter: ResourceFilter::default(),
+            rows: Default::default(),
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: SearchMessage) -> Command<Message> {
+        match message {
+            SearchMessage::Filter(message) => {
+                self.filter.update(message);
+                Command::none()
+            }
+            SearchMessage::ResourceMessage(resource_type, module_id, path, message) => {
+                Command::perform(
+                    async move { (resource_type, module_id, path, message) },
+                    Message::ResourceMessage,
+                )
+            }
+        }
+    }
+
+    pub fn view<'a>(
+        &'a mut self,
+        data: &Data,
+        modules_map: &'a HashMap<String, Module>,
+        theme: Theme,
+    ) -> Element<'a, SearchMessage> {
+        // Searching is only done once a search term or filter has been entered, since
+        // otherwise every resource would be listed.
+        let results: Element<_> = if self.filter.is_active() {
+            let filter = &self.filter;
+            let [files_rows, multimedia_rows, weblectures_rows, conferences_rows] = &mut self.rows;
+            let results = Column::new().spacing(20);
+            let results = view_results(
+                results,
+                "Files",
+                &data.files,
+                files_rows,
+                ResourceType::File,
+                filter,
+                modules_map,
+                theme,
+            );
+            let results = view_results(
+                results,
+                "Multimedia",
+                &data.multimedia,
+                multimedia_rows,
+                ResourceType::Multimedia,
+                filter,
+                modules_map,
+                theme,
+            );
+            let results = view_results(
+                results,
+                "Weblectures",
+                &data.weblectures,
+                weblectures_rows,
+                ResourceType::Weblecture,
+                filter,
+                modules_map,
+                theme,
+            );
+            let results = view_results(
+                results,
+                "Conferences",
+                &data.conferences,
+                conferences_rows,
+                ResourceType::Conference,
+                filter,
+                modules_map,
+                theme,
+            );
+
+            results.into()
+        } else {
+            Text::new("Search files, multimedia, weblectures and conferences").into()
+        };
+
+        let content = Column::new()
+            .spacing(20)
+            .push(self.filter.view(theme).map(SearchMessage::Filter))
+            .push(results);
+
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
+
+        Container::new(scrollable).height(Length::Fill).into()
+    }
+}
+
+fn view_results<'a>(
+    column: Column<'a, SearchMessage>,
+    title: &str,
+    data: &DataItems<ResourceState>,
+    rows: &'a mut Vec<ResourceButtons>,
+    resource_type: ResourceType,
+    filter: &ResourceFilter,
+    modules_map: &'a HashMap<String, Module>,
+    theme: Theme,
+) -> Column<'a, SearchMessage> {
+    let matching = data
+        .items
+        .iter()
+        .filter(|file| filter.matches(file, modules_map))
+        .collect::<Vec<_>>();
+
+    if matching.is_empty() {
+        column
+    } else {
+        let column = column.push(Text::new(format!("{} ({})", title, matching.len())).size(24));
+
+        let rows = resource_buttons(rows, matching.len());
+        matching
+            .into_iter()
+            .zip(rows.iter_mut())
+            .fold(column, |column, (file, buttons)| {
+                let resource_module_id = file.module_id.clone();
+                let resource_path = file.path.clone();
+                column.push(file.view(buttons, modules_map, resource_type, theme).map(
+                    move |message| {
+                        SearchMessage::ResourceMessage(
+                            resource_type,
+                            resource_module_id.clone(),
+                            resource_path.clone(),
+                            message,
+                        )
+                    },
+                ))
+            })
+    }
+}
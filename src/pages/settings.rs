@@ -1,16 +1,27 @@
+use std::path::Path;
+
 use iced::{
-    button, scrollable, Align, Button, Checkbox, Column, Command, Container, Element, Length, Row,
-    Scrollable, Text,
+    button, pick_list, scrollable, text_input, Align, Button, Checkbox, Column, Command, Container,
+    Element, Length, PickList, Row, Scrollable, Text, TextInput,
 };
 
+use crate::layout::{is_valid_folder_layout, resolve_folder_layout, DEFAULT_FOLDER_LAYOUT};
 use crate::message::Message;
+use crate::module::Module;
+use crate::opener::OpenerRuleEdit;
 use crate::pages::Page;
+use crate::resource::ResourceType;
 use crate::settings::Settings;
+use crate::theme::{self, Theme};
 
 #[derive(Debug, Clone)]
 pub struct SettingsPage {
     login_button: button::State,
     download_location_button: button::State,
+    folder_layout_input: text_input::State,
+    theme_list: pick_list::State<Theme>,
+    opener_rows: Vec<OpenerRuleRow>,
+    add_opener_button: button::State,
     is_changing_download_location: bool,
     scroll: scrollable::State,
 }
@@ -20,8 +31,19 @@ pub enum SettingsMessage {
     SwitchPage(Page),
     ToggleSaveUsername(bool),
     ToggleSavePassword(bool),
+    ToggleKeepPreviousVersions(bool),
+    ThemeSelected(Theme),
     ChangeDownloadLocation,
     DownloadLocationChanged,
+    FolderLayoutChanged(String),
+    EditOpenerRules(OpenerRuleEdit),
+}
+
+#[derive(Debug, Clone, Default)]
+struct OpenerRuleRow {
+    extensions_input: text_input::State,
+    command_input: text_input::State,
+    remove_button: button::State,
 }
 
 impl SettingsPage {
@@ -29,6 +51,10 @@ impl SettingsPage {
         SettingsPage {
             login_button: button::State::new(),
             download_location_button: button::State::new(),
+            folder_layout_input: text_input::State::new(),
+            theme_list: pick_list::State::default(),
+            opener_rows: vec![],
+            add_opener_button: button::State::new(),
             is_changing_download_location: false,
             scroll: scrollable::State::new(),
         }
@@ -45,6 +71,15 @@ impl SettingsPage {
             SettingsMessage::ToggleSavePassword(save_password) => {
                 Command::perform(async move { save_password }, Message::ToggleSavePassword)
             }
+            SettingsMessage::ToggleKeepPreviousVersions(keep_previous_versions) => {
+                Command::perform(
+                    async move { keep_previous_versions },
+                    Message::ToggleKeepPreviousVersions,
+                )
+            }
+            SettingsMessage::ThemeSelected(theme) => {
+                Command::perform(async move { theme }, Message::ThemeChanged)
+            }
             SettingsMessage::ChangeDownloadLocation => {
                 self.is_changing_download_location = true;
                 Command::perform(async {}, Message::ChangeDownloadLocation)
@@ -53,10 +88,18 @@ impl SettingsPage {
                 self.is_changing_download_location = false;
                 Command::none()
             }
+            SettingsMessage::FolderLayoutChanged(folder_layout) => {
+                Command::perform(async move { folder_layout }, Message::FolderLayoutChanged)
+            }
+            SettingsMessage::EditOpenerRules(edit) => {
+                Command::perform(async move { edit }, Message::OpenerRulesEdited)
+            }
         }
     }
 
     pub fn view(&mut self, settings: &mut Settings, logged_in: bool) -> Element<SettingsMessage> {
+        let theme = settings.get_theme();
+
         let login_element: Element<_> = if logged_in {
             Text::new("Logged in").into()
         } else {
@@ -67,6 +110,7 @@ impl SettingsPage {
                 .push(Text::new("Not logged in"))
                 .push(
                     Button::new(&mut self.login_button, Text::new("Login"))
+                        .style(theme::Button(theme))
                         .on_press(SettingsMessage::SwitchPage(Page::Login)),
                 )
                 .into()
@@ -78,7 +122,8 @@ impl SettingsPage {
                 "Save username",
                 SettingsMessage::ToggleSaveUsername,
             )
-            .width(Length::Fill);
+            .width(Length::Fill)
+            .style(theme::Checkbox(theme));
 
             Row::new()
                 .spacing(20)
@@ -93,7 +138,24 @@ impl SettingsPage {
                 "Save password",
                 SettingsMessage::ToggleSavePassword,
             )
-            .width(Length::Fill);
+            .width(Length::Fill)
+            .style(theme::Checkbox(theme));
+
+            Row::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(checkbox)
+                .into()
+        };
+
+        let keep_previous_versions_row: Element<_> = {
+            let checkbox = Checkbox::new(
+                settings.get_keep_previous_versions(),
+                "Keep previous versions of files which are updated",
+                SettingsMessage::ToggleKeepPreviousVersions,
+            )
+            .width(Length::Fill)
+            .style(theme::Checkbox(theme));
 
             Row::new()
                 .spacing(20)
@@ -102,6 +164,22 @@ impl SettingsPage {
                 .into()
         };
 
+        let theme_row: Element<_> = Row::new()
+            .height(Length::Units(30))
+            .align_items(Align::Center)
+            .spacing(20)
+            .push(Text::new("Theme"))
+            .push(
+                PickList::new(
+                    &mut self.theme_list,
+                    &Theme::ALL[..],
+                    Some(theme),
+                    SettingsMessage::ThemeSelected,
+                )
+                .style(theme::PickList(theme)),
+            )
+            .into();
+
         let download_location_details: Element<SettingsMessage> = {
             let download_location: Element<_> =
                 if let Some(download_location) = settings.get_download_location() {
@@ -111,7 +189,8 @@ impl SettingsPage {
                 };
 
             let download_location_button =
-                Button::new(&mut self.download_location_button, Text::new("Change…"));
+                Button::new(&mut self.download_location_button, Text::new("Change…"))
+                    .style(theme::Button(theme));
 
             // Disable change button if dialog is currently open/opening.
             // TODO: opening the dialog seems to take a bit of time on my Linux system.
@@ -131,16 +210,130 @@ impl SettingsPage {
                 .into()
         };
 
+        // The layout is previewed with an example file, since modules might not have been
+        // loaded yet.
+        let folder_layout_column: Element<_> = {
+            let mut example_module = Module::empty();
+            example_module.term = "2110".to_string();
+            example_module.code = "CS2030S".to_string();
+            example_module.name = "Programming Methodology II".to_string();
+            let preview = resolve_folder_layout(
+                settings.get_folder_layout(),
+                Some(&example_module),
+                ResourceType::File.name(),
+                Path::new("Lectures/Lecture 1.pdf"),
+            );
+
+            Column::new()
+                .spacing(10)
+                .push(
+                    Row::new()
+                        .spacing(20)
+                        .align_items(Align::Center)
+                        .push(Text::new("Folder layout"))
+                        .push(
+                            TextInput::new(
+                                &mut self.folder_layout_input,
+                                DEFAULT_FOLDER_LAYOUT,
+                                settings.get_folder_layout(),
+                                SettingsMessage::FolderLayoutChanged,
+                            )
+                            .padding(5)
+                            .style(theme::TextInput(theme)),
+                        ),
+                )
+                .push(Text::new(
+                    "Placeholders: {term}, {code}, {name}, {type} and {path}. Files which have already been downloaded will not be moved.",
+                ))
+                .push(Text::new(if is_valid_folder_layout(settings.get_folder_layout()) {
+                    format!("Preview: {}", preview.display())
+                } else {
+                    format!(
+                        "{{path}} has to be at the end of the layout, so the default layout is used: {}",
+                        preview.display()
+                    )
+                }))
+                .into()
+        };
+
+        // Files are opened with the first rule matching their extension, or the system
+        // default otherwise.
+        let opener_rules = settings.get_opener_rules();
+        self.opener_rows
+            .resize_with(opener_rules.len(), Default::default);
+        let opener_rules_column = opener_rules.iter().zip(self.opener_rows.iter_mut()).enumerate().fold(
+            Column::new()
+                .spacing(10)
+                .push(Text::new("Open files with"))
+                .push(Text::new(
+                    "Extensions are separated by commas. The path of the file replaces {} in the command, or is added to the end. Quote arguments containing spaces.",
+                )),
+            |column, (index, (rule, row))| {
+                column.push(
+                    Row::new()
+                        .spacing(20)
+                        .align_items(Align::Center)
+                        .push(
+                            TextInput::new(
+                                &mut row.extensions_input,
+                                "mp4, mkv",
+                                &rule.extensions,
+                                move |extensions| {
+                                    SettingsMessage::EditOpenerRules(
+                                        OpenerRuleEdit::ExtensionsChanged(index, extensions),
+                                    )
+                                },
+                            )
+                            .padding(5)
+                            .width(Length::FillPortion(1))
+                            .style(theme::TextInput(theme)),
+                        )
+                        .push(
+                            TextInput::new(
+                                &mut row.command_input,
+                                "mpv",
+                                &rule.command,
+                                move |command| {
+                                    SettingsMessage::EditOpenerRules(
+                                        OpenerRuleEdit::CommandChanged(index, command),
+                                    )
+                                },
+                            )
+                            .padding(5)
+                            .width(Length::FillPortion(2))
+                            .style(theme::TextInput(theme)),
+                        )
+                        .push(
+                            Button::new(&mut row.remove_button, Text::new("Remove"))
+                                .style(theme::Button(theme))
+                                .on_press(SettingsMessage::EditOpenerRules(
+                                    OpenerRuleEdit::Remove(index),
+                                )),
+                        ),
+                )
+            },
+        );
+        let opener_rules_column = opener_rules_column.push(
+            Button::new(&mut self.add_opener_button, Text::new("Add rule"))
+                .style(theme::Button(theme))
+                .on_press(SettingsMessage::EditOpenerRules(OpenerRuleEdit::Add)),
+        );
+
         let content = Column::new()
             .spacing(20)
             .push(login_element)
             .push(save_username_row)
             .push(save_password_row)
+            .push(keep_previous_versions_row)
+            .push(theme_row)
             .push(download_location_details)
-            .push(Text::new("Note: changing the download location will not shift files from the old location to the new one."));
+            .push(folder_layout_column)
+            .push(Text::new("Note: changing the download location will not shift files from the old location to the new one."))
+            .push(opener_rules_column);
 
-        let scrollable =
-            Scrollable::new(&mut self.scroll).push(Container::new(content).width(Length::Fill));
+        let scrollable = Scrollable::new(&mut self.scroll)
+            .style(theme::Scrollable(theme))
+            .push(Container::new(content).width(Length::Fill));
 
         Container::new(scrollable).height(Length::Fill).into()
     }
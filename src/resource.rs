@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use iced::{button, Align, Button, Element, Length, Row, Text};
+use iced::{
+    button, pick_list, tooltip, Align, Button, Element, Length, PickList, Row, Text, Tooltip,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -15,7 +18,12 @@ use fluminurs::{
 };
 
 use crate::data::FetchStatus;
+use crate::layout::{resolve_folder_layout, DEFAULT_FOLDER_LAYOUT};
 use crate::module::Module;
+use crate::theme::{self, Theme};
+use crate::utils::format_time;
+
+pub const ACTIONS_WIDTH: u16 = 520;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceState {
@@ -24,15 +32,117 @@ pub struct ResourceState {
     pub last_updated: SystemTime,
     pub download_path: Option<PathBuf>,
     pub download_time: Option<SystemTime>,
+    pub download_hash: Option<String>,
+    pub download_size: Option<u64>,
+    pub local_copy_path: Option<PathBuf>,
+    #[serde(default)]
+    pub history: Vec<ResourceVersion>,
+    pub first_seen: Option<SystemTime>,
+    #[serde(default)]
+    pub unseen: bool,
+    #[serde(default)]
+    pub removed: bool,
+    #[serde(default)]
+    pub metadata: ResourceMetadata,
+    #[serde(default)]
+    pub watch_status: WatchStatus,
 
     #[serde(skip)]
     pub resource: Option<Resource>,
     #[serde(skip)]
     pub download_status: FetchStatus,
     #[serde(skip)]
+    pub selected: bool,
+}
+
+// Button states for a displayed resource. These are kept by the page displaying the
+// resources, so that only resources which are actually displayed need them.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceButtons {
     open_button: button::State,
-    #[serde(skip)]
     download_button: button::State,
+    archive_button: button::State,
+    actions_list: pick_list::State<ResourceAction>,
+    watch_status_list: pick_list::State<WatchStatus>,
+}
+
+// A previous version of a resource which has since been updated on the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceVersion {
+    pub last_updated: SystemTime,
+    pub downloaded: bool,
+    pub local_path: Option<PathBuf>,
+}
+
+// Details about a resource which are recorded when it is fetched. Older data files don't
+// record any of these, so every field is optional. fluminurs doesn't expose the size of
+// files or the duration of videos, so they are only known once downloaded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceMetadata {
+    pub kind: Option<ResourceKind>,
+    // The lowercase file extension, and the MIME type guessed from it.
+    pub extension: Option<String>,
+    pub mime_type: Option<String>,
+    // Whether the file is within a folder which students can upload submissions to.
+    #[serde(default)]
+    pub in_uploadable_folder: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ResourceKind {
+    File,
+    InternalVideo,
+    ExternalVideo,
+    WebLectureVideo,
+    ZoomRecording,
+}
+
+impl ResourceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceKind::File => "File",
+            ResourceKind::InternalVideo => "Video",
+            ResourceKind::ExternalVideo => "External video",
+            ResourceKind::WebLectureVideo => "Web lecture",
+            ResourceKind::ZoomRecording => "Zoom recording",
+        }
+    }
+}
+
+// How much of a video has been watched, which is recorded manually.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchStatus {
+    Unwatched,
+    PartiallyWatched,
+    Watched,
+}
+
+impl WatchStatus {
+    pub const ALL: [WatchStatus; 3] = [
+        WatchStatus::Unwatched,
+        WatchStatus::PartiallyWatched,
+        WatchStatus::Watched,
+    ];
+}
+
+impl Default for WatchStatus {
+    fn default() -> Self {
+        WatchStatus::Unwatched
+    }
+}
+
+impl fmt::Display for WatchStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                WatchStatus::Unwatched => "Unwatched",
+                WatchStatus::PartiallyWatched => "Partially watched",
+                WatchStatus::Watched => "Watched",
+            }
+        )
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -43,6 +153,17 @@ pub enum ResourceType {
     Conference,
 }
 
+impl ResourceType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceType::File => "Files",
+            ResourceType::Multimedia => "Multimedia",
+            ResourceType::Weblecture => "Weblectures",
+            ResourceType::Conference => "Conferences",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Resource {
     File(File),
@@ -56,6 +177,59 @@ pub enum Resource {
 pub enum ResourceMessage {
     OpenResource,
     DownloadResource,
+    ArchiveResource,
+    ShowInFolder,
+    DeleteLocalCopy,
+    CopyPath,
+    CopyLink,
+    WatchStatusSelected(WatchStatus),
+}
+
+// Less common actions, which are listed in a menu next to each resource.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResourceAction {
+    // Displayed as the title of the menu, and never listed in it.
+    Menu,
+    ShowInFolder,
+    DeleteLocalCopy,
+    CopyPath,
+    CopyLink,
+}
+
+impl ResourceAction {
+    const DOWNLOADED_ACTIONS: [ResourceAction; 4] = [
+        ResourceAction::ShowInFolder,
+        ResourceAction::DeleteLocalCopy,
+        ResourceAction::CopyPath,
+        ResourceAction::CopyLink,
+    ];
+    const ACTIONS: [ResourceAction; 1] = [ResourceAction::CopyLink];
+
+    fn message(&self) -> ResourceMessage {
+        match self {
+            ResourceAction::Menu => unreachable!("the menu title can't be selected"),
+            ResourceAction::ShowInFolder => ResourceMessage::ShowInFolder,
+            ResourceAction::DeleteLocalCopy => ResourceMessage::DeleteLocalCopy,
+            ResourceAction::CopyPath => ResourceMessage::CopyPath,
+            ResourceAction::CopyLink => ResourceMessage::CopyLink,
+        }
+    }
+}
+
+impl fmt::Display for ResourceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ResourceAction::Menu => "More",
+                ResourceAction::ShowInFolder => "Show in folder",
+                ResourceAction::DeleteLocalCopy => "Delete local copy",
+                ResourceAction::CopyPath => "Copy path",
+                ResourceAction::CopyLink => "Copy LumiNUS link",
+            }
+        )
+    }
 }
 
 impl ResourceState {
@@ -66,10 +240,18 @@ impl ResourceState {
             last_updated: SystemTime::UNIX_EPOCH,
             download_path: None,
             download_time: None,
+            download_hash: None,
+            download_size: None,
+            local_copy_path: None,
+            history: vec![],
+            first_seen: None,
+            unseen: false,
+            removed: false,
+            metadata: ResourceMetadata::default(),
+            watch_status: WatchStatus::Unwatched,
             resource: None,
             download_status: FetchStatus::Idle,
-            open_button: button::State::new(),
-            download_button: button::State::new(),
+            selected: false,
         }
     }
 
@@ -80,79 +262,352 @@ impl ResourceState {
             last_updated: get_resource_last_updated(&resource),
             download_path: None,
             download_time: None,
+            download_hash: None,
+            download_size: None,
+            local_copy_path: None,
+            history: vec![],
+            first_seen: Some(SystemTime::now()),
+            unseen: true,
+            removed: false,
+            metadata: get_resource_metadata(&resource),
+            watch_status: WatchStatus::Unwatched,
 
             resource: Some(resource),
             download_status: FetchStatus::Idle,
-            open_button: button::State::new(),
-            download_button: button::State::new(),
+            selected: false,
         }
     }
 
+    // Location of the resource within the download location, following the given
+    // folder layout.
     pub fn local_resource_path(
         &self,
         modules_map: &HashMap<String, Module>,
         resource_type: ResourceType,
+        folder_layout: &str,
     ) -> PathBuf {
-        Path::new(match modules_map.get(&self.module_id) {
-            Some(module) => module.code.as_ref(),
-            None => "Unknown",
-        })
-        .join(Path::new(match &self.resource {
+        self.local_folder_path(modules_map, resource_type, folder_layout, &self.path)
+    }
+
+    // Location of the given folder containing the resource within the download location.
+    pub fn local_folder_path(
+        &self,
+        modules_map: &HashMap<String, Module>,
+        resource_type: ResourceType,
+        folder_layout: &str,
+        folder: &Path,
+    ) -> PathBuf {
+        let type_name = match &self.resource {
             Some(Resource::File(_)) => "Files",
             Some(Resource::InternalVideo(_)) => "Multimedia",
             Some(Resource::ExternalVideo(_)) => "Multimedia",
             Some(Resource::WebLectureVideo(_)) => "Weblectures",
             Some(Resource::ZoomRecording(_)) => "Conferences",
-            None => match resource_type {
-                ResourceType::File => "Files",
-                ResourceType::Multimedia => "Multimedia",
-                ResourceType::Weblecture => "Weblectures",
-                ResourceType::Conference => "Conferences",
-            },
-        }))
-        .join(self.path.clone())
-    }
-
-    pub fn view(
-        &mut self,
+            None => resource_type.name(),
+        };
+
+        resolve_folder_layout(
+            folder_layout,
+            modules_map.get(&self.module_id),
+            type_name,
+            folder,
+        )
+    }
+
+    // Labels always use the default layout, so that they show the module and type of
+    // each resource regardless of where it is downloaded to.
+    pub fn display_path(
+        &self,
         modules_map: &HashMap<String, Module>,
         resource_type: ResourceType,
-    ) -> Element<ResourceMessage> {
+    ) -> PathBuf {
+        self.local_resource_path(modules_map, resource_type, DEFAULT_FOLDER_LAYOUT)
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    // The file extension of the resource, or the kind of resource for videos without one.
+    // This is empty for resources stored before the kind of resource was recorded.
+    pub fn file_type(&self) -> String {
+        match self.path.extension() {
+            Some(extension) => extension.to_string_lossy().to_uppercase(),
+            None => self
+                .metadata
+                .kind
+                .map_or("", |kind| kind.name())
+                .to_string(),
+        }
+    }
+
+    // Whether the resource is a video, whose watch status can be recorded. This is unknown
+    // for resources stored before the kind of resource was recorded, until they are
+    // refreshed.
+    pub fn is_video(&self) -> bool {
+        self.metadata
+            .kind
+            .map_or(false, |kind| kind != ResourceKind::File)
+    }
+
+    // Link to the section of the module on LumiNUS containing the resource. The IDs of
+    // individual resources aren't recorded, so we can't link to the resource itself.
+    pub fn web_link(&self, resource_type: ResourceType) -> String {
+        format!(
+            "https://luminus.nus.edu.sg/modules/{}/{}",
+            self.module_id,
+            match resource_type {
+                ResourceType::File => "files",
+                ResourceType::Multimedia => "multimedia",
+                ResourceType::Weblecture => "weblecture",
+                ResourceType::Conference => "conferencing",
+            }
+        )
+    }
+
+    // Short description of the download state of the resource.
+    pub fn status(&self) -> &'static str {
+        if self.removed {
+            "Removed"
+        } else {
+            match self.download_status {
+                FetchStatus::Fetching => "Downloading…",
+                FetchStatus::Error => "Error",
+                FetchStatus::Idle if self.is_outdated() => "Outdated",
+                FetchStatus::Idle if self.download_path.is_some() => "Downloaded",
+                FetchStatus::Idle => "Not downloaded",
+            }
+        }
+    }
+
+    // Whether the resource has been updated on the server since it was downloaded.
+    pub fn is_outdated(&self) -> bool {
+        match (&self.download_path, self.download_time) {
+            (Some(_), Some(download_time)) => download_time < self.last_updated,
+            _ => false,
+        }
+    }
+
+    fn revisions(&self) -> String {
+        let mut revisions = format!("{} (latest)", format_time(self.last_updated));
+
+        for version in self.history.iter().rev() {
+            revisions.push('\n');
+            revisions.push_str(&format_time(version.last_updated));
+            if let Some(local_path) = &version.local_path {
+                revisions.push_str(&format!(" (kept at {})", local_path.display()));
+            } else if version.downloaded {
+                revisions.push_str(" (downloaded)");
+            }
+        }
+
+        revisions
+    }
+
+    pub fn view<'a>(
+        &self,
+        buttons: &'a mut ResourceButtons,
+        modules_map: &HashMap<String, Module>,
+        resource_type: ResourceType,
+        theme: Theme,
+    ) -> Element<'a, ResourceMessage> {
+        let label = self
+            .display_path(modules_map, resource_type)
+            .display()
+            .to_string();
+
+        self.view_with_label(buttons, label, theme)
+    }
+
+    pub fn view_with_label<'a>(
+        &self,
+        buttons: &'a mut ResourceButtons,
+        label: String,
+        theme: Theme,
+    ) -> Element<'a, ResourceMessage> {
         let content = Row::new()
             .height(Length::Units(30))
             .align_items(Align::Center)
             .spacing(20)
-            .push(Text::new(
-                self.local_resource_path(modules_map, resource_type)
-                    .display()
-                    .to_string(),
-            ));
+            .push(Text::new(label));
+
+        // The MIME type is shown when hovering over the file type, if it is known.
+        let file_type = self.file_type();
+        let content = match &self.metadata.mime_type {
+            Some(mime_type) if !file_type.is_empty() => content.push(
+                Tooltip::new(Text::new(file_type), mime_type, tooltip::Position::Bottom)
+                    .style(theme::Tooltip(theme)),
+            ),
+            _ if !file_type.is_empty() => content.push(Text::new(file_type)),
+            _ => content,
+        };
+
+        let content = if self.unseen {
+            content.push(Text::new("New"))
+        } else {
+            content
+        };
+
+        let content = if !self.history.is_empty() {
+            content.push(
+                Tooltip::new(
+                    Text::new("Updated"),
+                    self.revisions(),
+                    tooltip::Position::Bottom,
+                )
+                .style(theme::Tooltip(theme)),
+            )
+        } else {
+            content
+        };
+
+        let content = if let Some(local_copy_path) = &self.local_copy_path {
+            content.push(Text::new(format!(
+                "Local changes kept in {}",
+                local_copy_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            )))
+        } else {
+            content
+        };
 
+        let content = if self.removed {
+            content.push(Text::new("Removed from LumiNUS"))
+        } else {
+            content
+        };
+
+        self.push_actions(buttons, content, theme).into()
+    }
+
+    // Displays the resource as cells of text with the given widths, followed by its actions.
+    pub fn view_cells<'a>(
+        &self,
+        buttons: &'a mut ResourceButtons,
+        cells: Vec<(String, Length)>,
+        theme: Theme,
+    ) -> Element<'a, ResourceMessage> {
+        let content = cells.into_iter().fold(
+            Row::new()
+                .height(Length::Units(30))
+                .align_items(Align::Center)
+                .spacing(20),
+            |row, (text, width)| row.push(Text::new(text).width(width)),
+        );
+
+        // Actions are kept in a column of fixed width so that the cells of each row line up.
+        let actions = self.push_actions(
+            buttons,
+            Row::new()
+                .width(Length::Units(ACTIONS_WIDTH))
+                .align_items(Align::Center)
+                .spacing(20),
+            theme,
+        );
+
+        content.push(actions).into()
+    }
+
+    fn push_actions<'a>(
+        &self,
+        buttons: &'a mut ResourceButtons,
+        content: Row<'a, ResourceMessage>,
+        theme: Theme,
+    ) -> Row<'a, ResourceMessage> {
         let content = if let Some(_) = self.download_path {
             content.push(
-                Button::new(&mut self.open_button, Text::new("Open"))
+                Button::new(&mut buttons.open_button, Text::new("Open"))
+                    .style(theme::Button(theme))
                     .on_press(ResourceMessage::OpenResource),
             )
         } else {
             content
         };
 
-        let download_content: Element<_> = match self.download_status {
-            FetchStatus::Fetching => {
-                Button::new(&mut self.download_button, Text::new("Downloading…")).into()
+        // Resources which have been removed on the server can no longer be downloaded, but
+        // local copies can be moved to the archive folder.
+        let content = if self.removed {
+            if let Some(_) = self.download_path {
+                content.push(
+                    Button::new(&mut buttons.archive_button, Text::new("Archive"))
+                        .style(theme::Button(theme))
+                        .on_press(ResourceMessage::ArchiveResource),
+                )
+            } else {
+                content
             }
-            FetchStatus::Idle => Button::new(&mut self.download_button, Text::new("Download"))
-                .on_press(ResourceMessage::DownloadResource)
-                .into(),
-            FetchStatus::Error => Button::new(&mut self.download_button, Text::new("Error…"))
+        } else {
+            let is_outdated = self.is_outdated();
+            let download_content: Element<_> = match self.download_status {
+                FetchStatus::Fetching => {
+                    Button::new(&mut buttons.download_button, Text::new("Downloading…"))
+                        .style(theme::Button(theme))
+                        .into()
+                }
+                FetchStatus::Idle => Button::new(
+                    &mut buttons.download_button,
+                    Text::new(if is_outdated { "Update" } else { "Download" }),
+                )
+                .style(theme::Button(theme))
                 .on_press(ResourceMessage::DownloadResource)
                 .into(),
+                FetchStatus::Error => {
+                    Button::new(&mut buttons.download_button, Text::new("Error…"))
+                        .style(theme::Button(theme))
+                        .on_press(ResourceMessage::DownloadResource)
+                        .into()
+                }
+            };
+
+            content.push(download_content)
         };
 
-        content.push(download_content).into()
+        let content = if self.is_video() {
+            content.push(
+                PickList::new(
+                    &mut buttons.watch_status_list,
+                    &WatchStatus::ALL[..],
+                    Some(self.watch_status),
+                    ResourceMessage::WatchStatusSelected,
+                )
+                .style(theme::PickList(theme)),
+            )
+        } else {
+            content
+        };
+
+        let actions = match self.download_path {
+            Some(_) => &ResourceAction::DOWNLOADED_ACTIONS[..],
+            None => &ResourceAction::ACTIONS[..],
+        };
+        content.push(
+            PickList::new(
+                &mut buttons.actions_list,
+                actions,
+                Some(ResourceAction::Menu),
+                |action| action.message(),
+            )
+            .style(theme::PickList(theme)),
+        )
     }
 }
 
+// Returns button states for the given number of displayed resources, reusing the states
+// from the previous time the resources were displayed.
+pub fn resource_buttons(
+    buttons: &mut Vec<ResourceButtons>,
+    count: usize,
+) -> &mut [ResourceButtons] {
+    buttons.resize_with(count, ResourceButtons::default);
+    &mut buttons[..]
+}
+
 fn get_resource_path(resource: &Resource) -> PathBuf {
     match &resource {
         Resource::File(resource) => resource.path().to_path_buf(),
@@ -172,3 +627,51 @@ fn get_resource_last_updated(resource: &Resource) -> SystemTime {
         Resource::WebLectureVideo(resource) => resource.last_updated(),
     }
 }
+
+fn get_resource_metadata(resource: &Resource) -> ResourceMetadata {
+    let extension = get_resource_path(resource)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    let mime_type = extension
+        .as_deref()
+        .and_then(guess_mime_type)
+        .map(str::to_string);
+
+    ResourceMetadata {
+        kind: Some(match &resource {
+            Resource::File(_) => ResourceKind::File,
+            Resource::InternalVideo(_) => ResourceKind::InternalVideo,
+            Resource::ExternalVideo(_) => ResourceKind::ExternalVideo,
+            Resource::WebLectureVideo(_) => ResourceKind::WebLectureVideo,
+            Resource::ZoomRecording(_) => ResourceKind::ZoomRecording,
+        }),
+        extension,
+        mime_type,
+        in_uploadable_folder: false,
+    }
+}
+
+// MIME types of the kinds of files commonly uploaded to LumiNUS.
+fn guess_mime_type(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "zip" => "application/zip",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        _ => return None,
+    })
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::layout::DEFAULT_FOLDER_LAYOUT;
+use crate::opener::{OpenerRule, OpenerRuleEdit};
+use crate::resource::ResourceType;
+use crate::sort::ResourceSort;
 use crate::storage::{get_project_dirs, Storage};
+use crate::theme::Theme;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -11,6 +17,18 @@ pub struct Settings {
     save_username: bool,
     save_password: bool,
     download_location: Option<PathBuf>,
+    #[serde(default = "default_folder_layout")]
+    folder_layout: String,
+    #[serde(default)]
+    keep_previous_versions: bool,
+    // Sort order of each resources page, keyed by the name of the resource type.
+    #[serde(default)]
+    resource_sorts: HashMap<String, ResourceSort>,
+    #[serde(default)]
+    theme: Theme,
+    // Commands used to open downloaded files, checked in order.
+    #[serde(default)]
+    opener_rules: Vec<OpenerRule>,
 
     #[serde(skip)]
     dirty: bool,
@@ -27,6 +45,11 @@ impl Settings {
             save_username: true,
             save_password: false,
             download_location: Some(default_download_dir()),
+            folder_layout: default_folder_layout(),
+            keep_previous_versions: false,
+            resource_sorts: HashMap::new(),
+            theme: Theme::Light,
+            opener_rules: vec![],
             dirty: false,
             saving: false,
         }
@@ -67,6 +90,55 @@ impl Settings {
         self.dirty = true;
     }
 
+    pub fn set_folder_layout(&mut self, folder_layout: String) {
+        if self.folder_layout != folder_layout {
+            self.folder_layout = folder_layout;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_keep_previous_versions(&mut self, keep_previous_versions: bool) {
+        if self.keep_previous_versions != keep_previous_versions {
+            self.keep_previous_versions = keep_previous_versions;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_resource_sort(&mut self, resource_type: ResourceType, sort: ResourceSort) {
+        self.resource_sorts
+            .insert(resource_type.name().to_string(), sort);
+        self.dirty = true;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        if self.theme != theme {
+            self.theme = theme;
+            self.dirty = true;
+        }
+    }
+
+    pub fn edit_opener_rules(&mut self, edit: OpenerRuleEdit) {
+        match edit {
+            OpenerRuleEdit::Add => self.opener_rules.push(OpenerRule::default()),
+            OpenerRuleEdit::Remove(index) => {
+                if index < self.opener_rules.len() {
+                    self.opener_rules.remove(index);
+                }
+            }
+            OpenerRuleEdit::ExtensionsChanged(index, extensions) => {
+                if let Some(rule) = self.opener_rules.get_mut(index) {
+                    rule.extensions = extensions;
+                }
+            }
+            OpenerRuleEdit::CommandChanged(index, command) => {
+                if let Some(rule) = self.opener_rules.get_mut(index) {
+                    rule.command = command;
+                }
+            }
+        }
+        self.dirty = true;
+    }
+
     pub fn get_username(&self) -> &Option<String> {
         &self.username
     }
@@ -86,6 +158,29 @@ impl Settings {
     pub fn get_download_location(&self) -> &Option<PathBuf> {
         &self.download_location
     }
+
+    pub fn get_folder_layout(&self) -> &str {
+        &self.folder_layout
+    }
+
+    pub fn get_keep_previous_versions(&self) -> bool {
+        self.keep_previous_versions
+    }
+
+    pub fn get_theme(&self) -> Theme {
+        self.theme
+    }
+
+    pub fn get_opener_rules(&self) -> &Vec<OpenerRule> {
+        &self.opener_rules
+    }
+
+    pub fn get_resource_sort(&self, resource_type: ResourceType) -> ResourceSort {
+        match self.resource_sorts.get(resource_type.name()) {
+            Some(sort) => *sort,
+            None => ResourceSort::default(),
+        }
+    }
 }
 
 impl Storage for Settings {
@@ -105,6 +200,10 @@ impl Storage for Settings {
     }
 }
 
+fn default_folder_layout() -> String {
+    DEFAULT_FOLDER_LAYOUT.to_string()
+}
+
 pub fn default_download_dir() -> PathBuf {
     let mut download_dir: PathBuf = directories::UserDirs::new()
         .unwrap()
@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::module::Module;
+use crate::resource::ResourceState;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortColumn {
+    Name,
+    Module,
+    Type,
+    LastUpdated,
+    DownloadTime,
+    Size,
+    Status,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceSort {
+    pub column: SortColumn,
+    pub ascending: bool,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 7] = [
+        SortColumn::Name,
+        SortColumn::Module,
+        SortColumn::Type,
+        SortColumn::LastUpdated,
+        SortColumn::DownloadTime,
+        SortColumn::Size,
+        SortColumn::Status,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SortColumn::Name => "Name",
+            SortColumn::Module => "Module",
+            SortColumn::Type => "Type",
+            SortColumn::LastUpdated => "Last updated",
+            SortColumn::DownloadTime => "Downloaded",
+            SortColumn::Size => "Size",
+            SortColumn::Status => "Status",
+        }
+    }
+}
+
+impl ResourceSort {
+    pub fn default() -> Self {
+        // Sorting by module keeps the order the resources are stored in.
+        ResourceSort {
+            column: SortColumn::Module,
+            ascending: true,
+        }
+    }
+
+    // Sorting by the same column again reverses the order.
+    pub fn toggle(&self, column: SortColumn) -> Self {
+        ResourceSort {
+            column,
+            ascending: self.column != column || !self.ascending,
+        }
+    }
+
+    pub fn compare(
+        &self,
+        r1: &ResourceState,
+        r2: &ResourceState,
+        modules_map: &HashMap<String, Module>,
+    ) -> Ordering {
+        let ordering = match self.column {
+            SortColumn::Name => r1.name().to_lowercase().cmp(&r2.name().to_lowercase()),
+            SortColumn::Module => module_code(r1, modules_map)
+                .cmp(module_code(r2, modules_map))
+                .then_with(|| r1.path.cmp(&r2.path)),
+            SortColumn::Type => r1.file_type().cmp(&r2.file_type()),
+            SortColumn::LastUpdated => r1.last_updated.cmp(&r2.last_updated),
+            SortColumn::DownloadTime => r1.download_time.cmp(&r2.download_time),
+            SortColumn::Size => r1.download_size.cmp(&r2.download_size),
+            SortColumn::Status => r1.status().cmp(r2.status()),
+        };
+
+        if self.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    }
+}
+
+fn module_code<'a>(resource: &ResourceState, modules_map: &'a HashMap<String, Module>) -> &'a str {
+    match modules_map.get(&resource.module_id) {
+        Some(module) => &module.code,
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    fn resource(module_id: &str, path: &str) -> ResourceState {
+        let mut resource = ResourceState::empty();
+        resource.module_id = module_id.to_string();
+        resource.path = PathBuf::from(path);
+        resource
+    }
+
+    fn compare(column: SortColumn, r1: &ResourceState, r2: &ResourceState) -> Ordering {
+        let mut modules_map = HashMap::new();
+        for (id, code) in [("a", "CS2030S"), ("b", "CS1231S")].iter() {
+            let mut module = Module::empty();
+            module.id = id.to_string();
+            module.code = code.to_string();
+            modules_map.insert(module.id.clone(), module);
+        }
+
+        ResourceSort {
+            column,
+            ascending: true,
+        }
+        .compare(r1, r2, &modules_map)
+    }
+
+    #[test]
+    fn compares_names_ignoring_case() {
+        let r1 = resource("a", "Tutorials/b.pdf");
+        let r2 = resource("a", "Lectures/C.pdf");
+
+        assert_eq!(compare(SortColumn::Name, &r1, &r2), Ordering::Less);
+    }
+
+    #[test]
+    fn compares_module_codes_then_paths() {
+        let r1 = resource("a", "Lectures/a.pdf");
+        let r2 = resource("b", "Lectures/b.pdf");
+        let r3 = resource("a", "Tutorials/a.pdf");
+
+        assert_eq!(compare(SortColumn::Module, &r1, &r2), Ordering::Greater);
+        assert_eq!(compare(SortColumn::Module, &r1, &r3), Ordering::Less);
+    }
+
+    #[test]
+    fn compares_file_types() {
+        let r1 = resource("a", "Lectures/a.pdf");
+        let r2 = resource("a", "Lectures/a.mp4");
+
+        assert_eq!(compare(SortColumn::Type, &r1, &r2), Ordering::Greater);
+    }
+
+    #[test]
+    fn compares_times_and_sizes_with_missing_values_first() {
+        let mut r1 = resource("a", "a.pdf");
+        let mut r2 = resource("a", "b.pdf");
+        r1.last_updated = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        r2.last_updated = SystemTime::UNIX_EPOCH + Duration::from_secs(2);
+        r2.download_time = Some(r2.last_updated);
+        r2.download_size = Some(1024);
+
+        assert_eq!(compare(SortColumn::LastUpdated, &r1, &r2), Ordering::Less);
+        assert_eq!(compare(SortColumn::DownloadTime, &r1, &r2), Ordering::Less);
+        assert_eq!(compare(SortColumn::Size, &r1, &r2), Ordering::Less);
+    }
+
+    #[test]
+    fn compares_statuses() {
+        let mut r1 = resource("a", "a.pdf");
+        let r2 = resource("a", "b.pdf");
+        r1.download_path = Some(PathBuf::from("a.pdf"));
+
+        // "Downloaded" comes before "Not downloaded".
+        assert_eq!(compare(SortColumn::Status, &r1, &r2), Ordering::Less);
+    }
+
+    #[test]
+    fn reverses_descending_order() {
+        let r1 = resource("a", "a.pdf");
+        let r2 = resource("a", "b.pdf");
+        let sort = ResourceSort {
+            column: SortColumn::Name,
+            ascending: false,
+        };
+
+        assert_eq!(sort.compare(&r1, &r2, &HashMap::new()), Ordering::Greater);
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::SystemTime;
+
+use iced::{button, Align, Button, Column, Command, Element, Row, Rule, Text};
+
+use crate::data::{Data, DataItems, FetchStatus};
+use crate::message::Message;
+use crate::resource::{ResourceState, ResourceType};
+use crate::theme::{self, Theme};
+use crate::utils::{format_elapsed, format_time};
+
+#[derive(Debug, Clone)]
+pub struct StatusBar {
+    last_error: Option<(SystemTime, String)>,
+    dismiss_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum StatusBarMessage {
+    DismissError,
+}
+
+// Work which is being done in the background, apart from loading and downloading
+// resources, which is tracked in `Data`.
+pub struct BackgroundTasks {
+    pub signing_in: bool,
+    pub saving: bool,
+}
+
+impl StatusBar {
+    pub fn default() -> Self {
+        StatusBar {
+            last_error: None,
+            dismiss_button: button::State::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: StatusBarMessage) -> Command<Message> {
+        match message {
+            StatusBarMessage::DismissError => {
+                self.last_error = None;
+                Command::none()
+            }
+        }
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.last_error = Some((SystemTime::now(), error));
+    }
+
+    pub fn view(
+        &mut self,
+        data: &Data,
+        username: Option<&str>,
+        tasks: BackgroundTasks,
+        theme: Theme,
+    ) -> Element<StatusBarMessage> {
+        let user = Text::new(match username {
+            Some(username) => format!("Signed in as {}", username),
+            None => "Offline".to_string(),
+        });
+
+        // The most recent time that anything was refreshed successfully.
+        let last_refreshed = [
+            data.modules.last_updated,
+            data.files.last_updated,
+            data.multimedia.last_updated,
+            data.weblectures.last_updated,
+            data.conferences.last_updated,
+            data.announcements.last_updated,
+        ]
+        .iter()
+        .max()
+        .cloned()
+        .filter(|last_updated| *last_updated != SystemTime::UNIX_EPOCH);
+        let last_refreshed = Text::new(match last_refreshed {
+            Some(last_refreshed) => format!("Refreshed {}", format_elapsed(last_refreshed)),
+            None => "Never refreshed".to_string(),
+        });
+
+        let mut loading = vec![];
+        if matches!(data.modules.fetch_status, FetchStatus::Fetching) {
+            loading.push("Modules");
+        }
+        for (resource_type, items) in [
+            (ResourceType::File, &data.files),
+            (ResourceType::Multimedia, &data.multimedia),
+            (ResourceType::Weblecture, &data.weblectures),
+            (ResourceType::Conference, &data.conferences),
+        ]
+        .iter()
+        {
+            if matches!(items.fetch_status, FetchStatus::Fetching) {
+                loading.push(resource_type.name());
+            }
+        }
+        if matches!(data.announcements.fetch_status, FetchStatus::Fetching) {
+            loading.push("Announcements");
+        }
+        let downloading = [
+            &data.files,
+            &data.multimedia,
+            &data.weblectures,
+            &data.conferences,
+        ]
+        .iter()
+        .map(|items| downloading_count(items))
+        .sum::<usize>();
+
+        let mut tasks_text = vec![];
+        if tasks.signing_in {
+            tasks_text.push("Signing in".to_string());
+        }
+        if !loading.is_empty() {
+            tasks_text.push(format!("Loading {}", loading.join(", ")));
+        }
+        if downloading > 0 {
+            tasks_text.push(format!(
+                "Downloading {} {}",
+                downloading,
+                if downloading == 1 { "file" } else { "files" }
+            ));
+        }
+        if tasks.saving {
+            tasks_text.push("Saving".to_string());
+        }
+        let tasks = Text::new(if tasks_text.is_empty() {
+            "Idle".to_string()
+        } else {
+            tasks_text.join(" · ")
+        });
+
+        let content = Row::new()
+            .spacing(20)
+            .align_items(Align::Center)
+            .push(user)
+            .push(last_refreshed)
+            .push(tasks);
+
+        let content = if let Some((time, error)) = &self.last_error {
+            content
+                .push(
+                    Text::new(format!("{} ({})", error, format_time(*time)))
+                        .color(theme.error_color()),
+                )
+                .push(
+                    Button::new(&mut self.dismiss_button, Text::new("Dismiss"))
+                        .style(theme::Button(theme))
+                        .on_press(StatusBarMessage::DismissError),
+                )
+        } else {
+            content
+        };
+
+        Column::new()
+            .spacing(5)
+            .push(content)
+            .push(Rule::horizontal(0).style(theme::Divider(theme)))
+            .into()
+    }
+}
+
+fn downloading_count(items: &DataItems<ResourceState>) -> usize {
+    items
+        .items
+        .iter()
+        .filter(|file| matches!(file.download_status, FetchStatus::Fetching))
+        .count()
+}
@@ -41,13 +41,17 @@ pub trait Storage: Debug + Clone + Serialize + DeserializeOwned + Send {
         }
     }
 
-    async fn load() -> Result<Self, Error> {
-        let contents = tokio::fs::read_to_string(Self::path())
-            .await
-            .map_err(|_| Error {})?;
+    // Returns `None` if nothing has been saved yet, and an error if the saved file could
+    // not be read.
+    async fn load() -> Result<Option<Self>, Error> {
+        let contents = match tokio::fs::read_to_string(Self::path()).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(_) => return Err(Error {}),
+        };
 
         if let Ok(settings) = serde_json::from_str(&contents) {
-            Ok(settings)
+            Ok(Some(settings))
         } else {
             println!("Corrupt file found");
             Err(Error {})
@@ -0,0 +1,316 @@
+use std::fmt;
+
+use iced::{
+    button, checkbox, container, pick_list, rule, scrollable, text_input, Background, Color,
+};
+
+use iced_style::menu;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+    HighContrast,
+}
+
+struct Palette {
+    background: Color,
+    surface: Color,
+    text: Color,
+    primary: Color,
+    primary_text: Color,
+    border: Color,
+    border_width: f32,
+    input_background: Color,
+    focus_background: Color,
+    focus_border: Color,
+    placeholder: Color,
+    selection: Color,
+    error: Color,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    fn palette(&self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: Color::WHITE,
+                surface: Color::from_rgb(0.87, 0.87, 0.87),
+                text: Color::BLACK,
+                primary: Color::from_rgb(0.0, 0.0, 0.545),
+                primary_text: Color::WHITE,
+                border: Color::BLACK,
+                border_width: 1.0,
+                input_background: Color::WHITE,
+                focus_background: Color::from_rgb(1.0, 1.0, 0.878),
+                focus_border: Color::from_rgb(1.0, 0.0, 0.0),
+                placeholder: Color::from_rgb(0.753, 0.753, 0.753),
+                selection: Color::from_rgb(0.8, 0.8, 1.0),
+                error: Color::from_rgb(1.0, 0.0, 0.0),
+            },
+            Theme::Dark => Palette {
+                background: Color::from_rgb(0.13, 0.13, 0.15),
+                surface: Color::from_rgb(0.25, 0.25, 0.28),
+                text: Color::from_rgb(0.9, 0.9, 0.9),
+                primary: Color::from_rgb(0.35, 0.45, 0.85),
+                primary_text: Color::WHITE,
+                border: Color::from_rgb(0.45, 0.45, 0.5),
+                border_width: 1.0,
+                input_background: Color::from_rgb(0.18, 0.18, 0.2),
+                focus_background: Color::from_rgb(0.22, 0.22, 0.26),
+                focus_border: Color::from_rgb(0.55, 0.65, 1.0),
+                placeholder: Color::from_rgb(0.5, 0.5, 0.55),
+                selection: Color::from_rgb(0.3, 0.4, 0.7),
+                error: Color::from_rgb(1.0, 0.45, 0.45),
+            },
+            Theme::HighContrast => Palette {
+                background: Color::BLACK,
+                surface: Color::BLACK,
+                text: Color::WHITE,
+                primary: Color::from_rgb(1.0, 1.0, 0.0),
+                primary_text: Color::BLACK,
+                border: Color::WHITE,
+                border_width: 2.0,
+                input_background: Color::BLACK,
+                focus_background: Color::BLACK,
+                focus_border: Color::from_rgb(1.0, 1.0, 0.0),
+                placeholder: Color::from_rgb(0.75, 0.75, 0.75),
+                selection: Color::from_rgb(0.0, 0.4, 1.0),
+                error: Color::from_rgb(1.0, 0.4, 0.4),
+            },
+        }
+    }
+
+    pub fn error_color(&self) -> Color {
+        self.palette().error
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Light
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Theme::Light => "Light",
+                Theme::Dark => "Dark",
+                Theme::HighContrast => "High contrast",
+            }
+        )
+    }
+}
+
+// The background of the whole application, which also sets the default text color.
+pub struct Container(pub Theme);
+
+impl container::StyleSheet for Container {
+    fn style(&self) -> container::Style {
+        let palette = self.0.palette();
+        container::Style {
+            text_color: Some(palette.text),
+            background: Some(Background::Color(palette.background)),
+            ..container::Style::default()
+        }
+    }
+}
+
+pub struct Tooltip(pub Theme);
+
+impl container::StyleSheet for Tooltip {
+    fn style(&self) -> container::Style {
+        let palette = self.0.palette();
+        container::Style {
+            text_color: Some(palette.text),
+            background: Some(Background::Color(palette.surface)),
+            border_radius: 2.0,
+            border_width: palette.border_width,
+            border_color: palette.border,
+        }
+    }
+}
+
+pub struct Button(pub Theme);
+
+impl button::StyleSheet for Button {
+    fn active(&self) -> button::Style {
+        let palette = self.0.palette();
+        button::Style {
+            background: Some(Background::Color(palette.surface)),
+            border_radius: 2.0,
+            border_width: palette.border_width,
+            border_color: palette.border,
+            text_color: palette.text,
+            ..button::Style::default()
+        }
+    }
+
+    fn disabled(&self) -> button::Style {
+        let active = self.active();
+        let palette = self.0.palette();
+        button::Style {
+            text_color: palette.placeholder,
+            ..active
+        }
+    }
+}
+
+// A button which is highlighted, such as the header button of the current page.
+pub struct ActiveButton(pub Theme);
+
+impl button::StyleSheet for ActiveButton {
+    fn active(&self) -> button::Style {
+        let palette = self.0.palette();
+        button::Style {
+            background: Some(Background::Color(palette.primary)),
+            border_radius: 0.0,
+            border_width: 0.0,
+            text_color: palette.primary_text,
+            ..button::Style::default()
+        }
+    }
+}
+
+pub struct Checkbox(pub Theme);
+
+impl checkbox::StyleSheet for Checkbox {
+    fn active(&self, _is_checked: bool) -> checkbox::Style {
+        let palette = self.0.palette();
+        checkbox::Style {
+            background: Background::Color(palette.input_background),
+            checkmark_color: palette.text,
+            border_radius: 2.0,
+            border_width: palette.border_width,
+            border_color: palette.border,
+        }
+    }
+
+    fn hovered(&self, is_checked: bool) -> checkbox::Style {
+        let palette = self.0.palette();
+        checkbox::Style {
+            border_color: palette.focus_border,
+            ..self.active(is_checked)
+        }
+    }
+}
+
+pub struct TextInput(pub Theme);
+
+impl text_input::StyleSheet for TextInput {
+    fn active(&self) -> text_input::Style {
+        let palette = self.0.palette();
+        text_input::Style {
+            background: Background::Color(palette.input_background),
+            border_color: palette.border,
+            border_width: palette.border_width,
+            border_radius: 1.0,
+        }
+    }
+
+    fn focused(&self) -> text_input::Style {
+        let palette = self.0.palette();
+        text_input::Style {
+            background: Background::Color(palette.focus_background),
+            border_color: palette.focus_border,
+            border_width: palette.border_width,
+            border_radius: 1.0,
+        }
+    }
+
+    fn placeholder_color(&self) -> Color {
+        self.0.palette().placeholder
+    }
+
+    fn value_color(&self) -> Color {
+        self.0.palette().text
+    }
+
+    fn selection_color(&self) -> Color {
+        self.0.palette().selection
+    }
+}
+
+pub struct PickList(pub Theme);
+
+impl pick_list::StyleSheet for PickList {
+    fn menu(&self) -> menu::Style {
+        let palette = self.0.palette();
+        menu::Style {
+            text_color: palette.text,
+            background: Background::Color(palette.input_background),
+            border_width: palette.border_width,
+            border_color: palette.border,
+            selected_text_color: palette.primary_text,
+            selected_background: Background::Color(palette.primary),
+        }
+    }
+
+    fn active(&self) -> pick_list::Style {
+        let palette = self.0.palette();
+        pick_list::Style {
+            text_color: palette.text,
+            background: Background::Color(palette.input_background),
+            border_radius: 2.0,
+            border_width: palette.border_width,
+            border_color: palette.border,
+            icon_size: 0.7,
+        }
+    }
+
+    fn hovered(&self) -> pick_list::Style {
+        let palette = self.0.palette();
+        pick_list::Style {
+            border_color: palette.focus_border,
+            ..self.active()
+        }
+    }
+}
+
+pub struct Scrollable(pub Theme);
+
+impl scrollable::StyleSheet for Scrollable {
+    fn active(&self) -> scrollable::Scrollbar {
+        let palette = self.0.palette();
+        scrollable::Scrollbar {
+            background: None,
+            border_radius: 5.0,
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+            scroller: scrollable::Scroller {
+                color: palette.border,
+                border_radius: 5.0,
+                border_width: 0.0,
+                border_color: Color::TRANSPARENT,
+            },
+        }
+    }
+
+    fn hovered(&self) -> scrollable::Scrollbar {
+        let palette = self.0.palette();
+        scrollable::Scrollbar {
+            background: Some(Background::Color(palette.surface)),
+            ..self.active()
+        }
+    }
+}
+
+pub struct Divider(pub Theme);
+
+impl rule::StyleSheet for Divider {
+    fn style(&self) -> rule::Style {
+        rule::Style {
+            fill_mode: rule::FillMode::Full,
+            color: self.0.palette().border,
+            width: 1,
+            radius: 1.0,
+        }
+    }
+}
@@ -1,10 +1,14 @@
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::SystemTime;
-use std::{cmp::Ordering, collections::HashMap};
 
+use chrono::offset::Utc;
+use chrono::DateTime;
+
+use crate::announcement::Announcement;
 use crate::data::DataItems;
 use crate::module::Module;
-use crate::resource::ResourceState;
+use crate::resource::{ResourceState, ResourceVersion};
 
 pub fn clean_username(username: &str) -> String {
     let username = username.to_lowercase();
@@ -15,6 +19,45 @@ pub fn clean_username(username: &str) -> String {
     }
 }
 
+pub fn format_time(time: SystemTime) -> String {
+    let time: DateTime<Utc> = time.into();
+    time.format("%d/%m/%Y %T").to_string()
+}
+
+// Describes how long ago the given time was, to the nearest minute.
+pub fn format_elapsed(time: SystemTime) -> String {
+    let minutes = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or_default()
+        .as_secs()
+        / 60;
+
+    match minutes {
+        0 => "just now".to_string(),
+        1 => "1 minute ago".to_string(),
+        2..=59 => format!("{} minutes ago", minutes),
+        60..=119 => "1 hour ago".to_string(),
+        120..=1439 => format!("{} hours ago", minutes / 60),
+        _ => format_time(time),
+    }
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if size < 1024 {
+        format!("{} B", size)
+    } else {
+        let mut size = size as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
 pub fn construct_modules_map(modules: &[Module]) -> HashMap<String, Module> {
     // TODO: avoid cloning everything
     modules
@@ -45,6 +88,9 @@ pub fn merge_modules(modules: &mut DataItems<Module>, mut new: DataItems<Module>
         .iter_mut()
         .fold(&mut Module::empty(), |mut prev, curr| {
             if prev.term == curr.term && prev.code == curr.code {
+                // Keep track of whether the module has already been seen.
+                curr.first_seen = prev.first_seen;
+                curr.unseen = prev.unseen;
                 prev.last_updated = SystemTime::UNIX_EPOCH;
 
                 prev
@@ -61,29 +107,26 @@ pub fn merge_modules(modules: &mut DataItems<Module>, mut new: DataItems<Module>
 pub fn merge_resources(
     resources: &mut DataItems<ResourceState>,
     mut new: DataItems<ResourceState>,
+    loaded_module_ids: &[String],
 ) {
+    let fetched: HashSet<(String, PathBuf)> = new
+        .items
+        .iter()
+        .map(|resource| (resource.module_id.clone(), resource.path.clone()))
+        .collect();
+
     // We assume that the more recently fetched data is always going to be fresher
     // than the local data, and merge without checking.
     resources.last_updated = new.last_updated;
     resources.fetch_status = new.fetch_status;
     resources.items.append(&mut new.items);
 
-    // Sort by module ID, followed by path, last updated time and whether there is a
-    // local download.
-    resources.items.sort_unstable_by(|m1, m2| {
+    // Sort by module ID, followed by path. The sort is stable, so the persisted resource
+    // always comes before the newly fetched one, even when nothing else differs.
+    resources.items.sort_by(|m1, m2| {
         m1.module_id
             .cmp(&m2.module_id)
             .then_with(|| m1.path.cmp(&m2.path))
-            .then_with(|| m1.last_updated.cmp(&m2.last_updated))
-            .then_with(|| {
-                if let Some(_) = m1.download_path {
-                    Ordering::Less
-                } else if let Some(_) = m2.download_path {
-                    Ordering::Greater
-                } else {
-                    Ordering::Equal
-                }
-            })
     });
 
     resources
@@ -91,14 +134,28 @@ pub fn merge_resources(
         .iter_mut()
         .fold(&mut ResourceState::empty(), |prev, curr| {
             // We keep the older resource, since it would contain our persisted information
-            // about download path and time, and update its last updated timing from the new
-            // resource.
+            // about download path and time, whether it has been seen and watched, and its
+            // history, and update its last updated timing from the new resource.
             if prev.module_id == curr.module_id && prev.path == curr.path {
                 curr.path = PathBuf::new();
                 std::mem::swap(&mut prev.last_updated, &mut curr.last_updated);
 
+                // Keep a record of the previous version if the resource has been updated, and
+                // mark the resource as unseen again.
+                if prev.last_updated != curr.last_updated {
+                    prev.unseen = true;
+                    prev.history.push(ResourceVersion {
+                        last_updated: curr.last_updated,
+                        downloaded: prev
+                            .download_time
+                            .map_or(false, |download_time| download_time >= curr.last_updated),
+                        local_path: None,
+                    });
+                }
+
                 if let Some(_) = curr.resource {
                     std::mem::swap(&mut prev.resource, &mut curr.resource);
+                    std::mem::swap(&mut prev.metadata, &mut curr.metadata);
                 }
 
                 prev
@@ -110,4 +167,217 @@ pub fn merge_resources(
     resources
         .items
         .retain(|resource| !resource.path.as_os_str().is_empty());
+
+    // Resources which were not returned when their module was loaded successfully have
+    // been removed on the server.
+    for resource in resources.items.iter_mut() {
+        if loaded_module_ids.contains(&resource.module_id) {
+            resource.removed =
+                !fetched.contains(&(resource.module_id.clone(), resource.path.clone()));
+        }
+    }
+}
+
+pub fn merge_announcements(
+    announcements: &mut DataItems<Announcement>,
+    new: DataItems<Announcement>,
+    loaded_module_ids: &[String],
+) {
+    announcements.last_updated = new.last_updated;
+    announcements.fetch_status = new.fetch_status;
+
+    // Announcements of modules which were loaded successfully are replaced, keeping
+    // track of which of them have already been read. Expired announcements are no longer
+    // returned, so they are dropped.
+    let (kept, replaced): (Vec<_>, Vec<_>) = announcements
+        .items
+        .drain(..)
+        .partition(|announcement| !loaded_module_ids.contains(&announcement.module_id));
+    let previous: HashMap<String, Announcement> = replaced
+        .into_iter()
+        .map(|announcement| (announcement.id.clone(), announcement))
+        .collect();
+
+    // LumiNUS doesn't give the date announcements were posted, so they are kept in the
+    // order they were fetched in, followed by those of modules which failed to load.
+    let mut merged = new
+        .items
+        .into_iter()
+        .map(|mut announcement| {
+            if let Some(previous) = previous.get(&announcement.id) {
+                announcement.first_seen = previous.first_seen;
+                announcement.unread = previous.unread;
+            }
+            announcement
+        })
+        .collect::<Vec<_>>();
+    merged.extend(kept);
+    announcements.items = merged;
+}
+
+// Converts the HTML body of an announcement to plain text, keeping line breaks between
+// paragraphs and list items.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = rest[start + 1..end]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match tag.as_str() {
+            "br" | "p" | "div" | "tr" | "h1" | "h2" | "h3" | "h4" => text.push('\n'),
+            "li" if !rest[start + 1..].starts_with('/') => text.push_str("\n• "),
+            _ => {}
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    // Collapse the blank lines left by nested block elements.
+    text.lines()
+        .map(str::trim_end)
+        .fold(Vec::<&str>::new(), |mut lines, line| {
+            if !(line.trim().is_empty() && lines.last().map_or(true, |last| last.is_empty())) {
+                lines.push(if line.trim().is_empty() { "" } else { line });
+            }
+            lines
+        })
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::resource::WatchStatus;
+
+    fn resource(last_updated: SystemTime) -> ResourceState {
+        let mut resource = ResourceState::empty();
+        resource.module_id = "module".to_string();
+        resource.path = PathBuf::from("Lectures/Lecture 1.pdf");
+        resource.last_updated = last_updated;
+        resource
+    }
+
+    #[test]
+    fn merge_resources_keeps_unchanged_undownloaded_resource() {
+        let last_updated = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let first_seen = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000);
+
+        let mut persisted = resource(last_updated);
+        persisted.first_seen = Some(first_seen);
+        persisted.unseen = false;
+        persisted.watch_status = WatchStatus::Watched;
+        let mut resources = DataItems::default();
+        resources.items.push(persisted);
+
+        let mut fetched = resource(last_updated);
+        fetched.first_seen = Some(SystemTime::now());
+        fetched.unseen = true;
+        let mut new = DataItems::default();
+        new.items.push(fetched);
+
+        merge_resources(&mut resources, new, &["module".to_string()]);
+
+        assert_eq!(resources.items.len(), 1);
+        let merged = &resources.items[0];
+        assert_eq!(merged.first_seen, Some(first_seen));
+        assert!(!merged.unseen);
+        assert_eq!(merged.watch_status, WatchStatus::Watched);
+        assert!(merged.history.is_empty());
+        assert!(!merged.removed);
+    }
+
+    #[test]
+    fn merge_resources_records_updated_version() {
+        let downloaded_version = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let updated_version = SystemTime::UNIX_EPOCH + Duration::from_secs(3_000_000);
+
+        let mut persisted = resource(downloaded_version);
+        persisted.download_time = Some(downloaded_version);
+        persisted.unseen = false;
+        let mut resources = DataItems::default();
+        resources.items.push(persisted);
+
+        let mut new = DataItems::default();
+        new.items.push(resource(updated_version));
+
+        merge_resources(&mut resources, new, &["module".to_string()]);
+
+        assert_eq!(resources.items.len(), 1);
+        let merged = &resources.items[0];
+        assert_eq!(merged.last_updated, updated_version);
+        assert_eq!(merged.download_time, Some(downloaded_version));
+        assert!(merged.unseen);
+        assert_eq!(merged.history.len(), 1);
+        assert_eq!(merged.history[0].last_updated, downloaded_version);
+        assert!(merged.history[0].downloaded);
+    }
+
+    #[test]
+    fn merge_resources_adds_new_resource_as_unseen() {
+        let last_updated = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let first_seen = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000);
+
+        let mut resources = DataItems::default();
+        let mut fetched = resource(last_updated);
+        fetched.first_seen = Some(first_seen);
+        fetched.unseen = true;
+        let mut new = DataItems::default();
+        new.items.push(fetched);
+
+        merge_resources(&mut resources, new, &["module".to_string()]);
+
+        assert_eq!(resources.items.len(), 1);
+        let merged = &resources.items[0];
+        assert_eq!(merged.first_seen, Some(first_seen));
+        assert!(merged.unseen);
+        assert!(merged.history.is_empty());
+        assert!(!merged.removed);
+    }
+
+    #[test]
+    fn merge_resources_marks_missing_resources_of_loaded_modules_as_removed() {
+        let last_updated = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let mut loaded = resource(last_updated);
+        loaded.module_id = "loaded".to_string();
+        let mut failed = resource(last_updated);
+        failed.module_id = "failed".to_string();
+        let mut resources = DataItems::default();
+        resources.items.push(loaded);
+        resources.items.push(failed);
+
+        // Only the module which was loaded successfully is able to detect removals.
+        merge_resources(
+            &mut resources,
+            DataItems::default(),
+            &["loaded".to_string()],
+        );
+
+        assert_eq!(resources.items.len(), 2);
+        for resource in resources.items.iter() {
+            assert_eq!(resource.removed, resource.module_id == "loaded");
+        }
+    }
 }